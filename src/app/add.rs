@@ -1,6 +1,6 @@
 use log::{error, warn};
 
-use crate::catalog::types::{Alias, AliasCatalog};
+use crate::catalog::types::{Alias, AliasCatalog, AliasKind};
 use crate::cli::add::AddCommand;
 use crate::cli::interaction::{InteractionMode, prompt_overwrite_existing_alias};
 use crate::core::add::add_alias;
@@ -21,12 +21,24 @@ pub fn handle_add(
         error!("Global aliases are only supported in zsh.");
         return Err(Failure::UnsupportedGlobalAlias);
     }
+    if args.suffix && *shell != ShellType::Zsh {
+        error!("Suffix aliases are only supported in zsh.");
+        return Err(Failure::UnsupportedSuffixAlias);
+    }
     if !is_valid_alias_name(&args.name) {
         error!("Invalid alias name '{}'.", args.name);
         return Err(Failure::InvalidAliasName);
     }
 
-    let mut alias = Alias::new(args.command, !args.disabled, args.global);
+    let kind = if args.global {
+        AliasKind::Global
+    } else if args.suffix {
+        AliasKind::Suffix
+    } else {
+        AliasKind::Regular
+    };
+    let mut alias = Alias::new(args.command, !args.disabled, kind);
+    alias.function = args.function;
     alias.description = args.description;
     alias.tags.extend(args.tag);
 
@@ -40,7 +52,7 @@ pub fn handle_add(
         add_alias(catalog, &args.name, &alias)?
     };
 
-    if outcome == Outcome::CatalogChanged {
+    if outcome == Outcome::CatalogChanged && alias.kind.takes_command_position() {
         for warning in conflict_warnings([args.name.as_str()], shell)
             .get(&args.name)
             .into_iter()
@@ -22,7 +22,11 @@ fn format_report(report: &ValidationReport, shell: &ShellType, quiet: bool) -> (
 
     if quiet {
         return (standard, diagnostics);
-    } else if report.errors.is_empty() && report.warnings.is_empty() {
+    }
+    for note in &report.notes {
+        standard.push_str(&format!("NOTE: {note}\n"));
+    }
+    if report.errors.is_empty() && report.warnings.is_empty() {
         standard.push_str(&format!("OK: Catalog is valid for {shell}.\n"));
     } else {
         standard.push_str(&format!(
@@ -64,6 +68,7 @@ mod tests {
         let report = ValidationReport {
             errors: vec!["bad alias".into()],
             warnings: vec!["shell mismatch".into()],
+            notes: Vec::new(),
         };
 
         let (standard, diagnostics) = format_report(&report, &ShellType::Bash, false);
@@ -78,6 +83,7 @@ mod tests {
         let report = ValidationReport {
             errors: Vec::new(),
             warnings: Vec::new(),
+            notes: Vec::new(),
         };
 
         let (standard, diagnostics) = format_report(&report, &ShellType::Zsh, false);
@@ -91,6 +97,7 @@ mod tests {
         let report = ValidationReport {
             errors: vec!["bad alias".into()],
             warnings: vec!["shell mismatch".into()],
+            notes: Vec::new(),
         };
 
         let (standard, diagnostics) = format_report(&report, &ShellType::Bash, true);
@@ -98,4 +105,20 @@ mod tests {
         assert!(standard.is_empty());
         assert_eq!(diagnostics, "ERROR: bad alias\n");
     }
+
+    #[test]
+    fn notes_precede_the_summary_without_counting_as_warnings() {
+        let report = ValidationReport {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            notes: vec!["no prompt hook".into()],
+        };
+
+        let (standard, _) = format_report(&report, &ShellType::Posix, false);
+        assert_eq!(
+            standard,
+            "NOTE: no prompt hook\nOK: Catalog is valid for POSIX.\n"
+        );
+        assert!(format_report(&report, &ShellType::Posix, true).0.is_empty());
+    }
 }
@@ -1,6 +1,6 @@
 use log::warn;
 
-use crate::catalog::types::AliasCatalog;
+use crate::catalog::types::{AliasCatalog, AliasKind};
 use crate::cli::edit::EditCommand;
 use crate::core::conflict::conflict_warnings;
 use crate::core::edit::edit_alias;
@@ -32,16 +32,34 @@ pub fn handle_edit(
         alias.tags.remove(&tag);
     }
     if cmd.global {
-        if !alias.global && *shell != ShellType::Zsh {
+        if alias.kind != AliasKind::Global && *shell != ShellType::Zsh {
             return Err(Failure::UnsupportedGlobalAlias);
         }
-        alias.global = true;
+        alias.kind = AliasKind::Global;
     }
-    if cmd.no_global {
-        alias.global = false;
+    if cmd.suffix {
+        if alias.kind != AliasKind::Suffix && *shell != ShellType::Zsh {
+            return Err(Failure::UnsupportedSuffixAlias);
+        }
+        alias.kind = AliasKind::Suffix;
+    }
+    if cmd.no_global && alias.kind == AliasKind::Global
+        || cmd.no_suffix && alias.kind == AliasKind::Suffix
+    {
+        alias.kind = AliasKind::Regular;
+    }
+    if cmd.function || cmd.no_function {
+        alias.function = cmd.function;
+    }
+    if alias.function {
+        match alias.kind {
+            AliasKind::Regular => {}
+            AliasKind::Global => return Err(Failure::GlobalFunction),
+            AliasKind::Suffix => return Err(Failure::SuffixFunction),
+        }
     }
     let outcome = edit_alias(catalog, &cmd.name, &alias)?;
-    if outcome == Outcome::CatalogChanged {
+    if outcome == Outcome::CatalogChanged && alias.kind.takes_command_position() {
         for warning in conflict_warnings([cmd.name.as_str()], shell)
             .get(&cmd.name)
             .into_iter()
@@ -0,0 +1,94 @@
+use super::CommandOutcome;
+use crate::catalog::types::{AliasCatalog, Variable};
+use crate::cli::env::{EnvAction, EnvCommand};
+use crate::core::env::{remove_variable, set_variable, set_variable_enabled};
+use crate::core::{Failure, Outcome};
+
+fn format_variables(catalog: &AliasCatalog, tags: &[String]) -> String {
+    let variables = catalog
+        .variables
+        .iter()
+        .filter(|(_, variable)| tags.iter().all(|tag| variable.tags.contains(tag)))
+        .collect::<Vec<_>>();
+    if variables.is_empty() {
+        return "No variables found.".into();
+    }
+    let width = variables
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    variables
+        .into_iter()
+        .map(|(name, variable)| {
+            let mut line = format!("{name:width$}  {}", variable.value);
+            if !variable.enabled {
+                line.push_str("  (disabled)");
+            }
+            if let Some(description) = &variable.description {
+                line.push_str(&format!("  # {description}"));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn handle_env(catalog: &mut AliasCatalog, cmd: EnvCommand) -> Result<CommandOutcome, Failure> {
+    let outcome = match cmd.action {
+        EnvAction::List { tag } => {
+            return Ok(CommandOutcome::with_message(
+                Outcome::NoChanges,
+                format_variables(catalog, &tag),
+            ));
+        }
+        EnvAction::Set {
+            name,
+            value,
+            tag,
+            description,
+            disabled,
+        } => {
+            let mut variable = Variable::new(value, !disabled);
+            variable.description = description;
+            variable.tags.extend(tag);
+            variable.refresh_representation();
+            set_variable(catalog, &name, variable)?
+        }
+        EnvAction::Remove { name } => remove_variable(catalog, &name)?,
+        EnvAction::Enable { name } => set_variable_enabled(catalog, &name, true)?,
+        EnvAction::Disable { name } => set_variable_enabled(catalog, &name, false)?,
+    };
+    Ok(outcome.into())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_aligns_values_and_filters_by_tag() {
+        let mut catalog = AliasCatalog::new();
+        let mut editor = Variable::new("nvim".into(), true);
+        editor.tags.insert("editor".into());
+        editor.description = Some("Preferred editor".into());
+        catalog.variables.insert("EDITOR".into(), editor);
+        catalog
+            .variables
+            .insert("PAGER".into(), Variable::new("less".into(), false));
+
+        assert_eq!(
+            format_variables(&catalog, &[]),
+            "EDITOR  nvim  # Preferred editor\nPAGER   less  (disabled)"
+        );
+        assert_eq!(
+            format_variables(&catalog, &["editor".into()]),
+            "EDITOR  nvim  # Preferred editor"
+        );
+        assert_eq!(
+            format_variables(&catalog, &["missing".into()]),
+            "No variables found."
+        );
+    }
+}
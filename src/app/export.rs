@@ -0,0 +1,176 @@
+use log::warn;
+
+use super::list::{format_json, selected_aliases};
+use super::shell::ShellType;
+use crate::catalog::io::render_catalog;
+use crate::catalog::types::{AliasCatalog, Variable};
+use crate::cli::export::{ExportCommand, ExportFormat};
+use crate::cli::list::AliasSelection;
+use crate::core::export::export_script;
+use crate::core::{Failure, Outcome};
+
+fn export_shell(format: ExportFormat) -> Option<ShellType> {
+    match format {
+        ExportFormat::Bash => Some(ShellType::Bash),
+        ExportFormat::Zsh => Some(ShellType::Zsh),
+        ExportFormat::Fish => Some(ShellType::Fish),
+        ExportFormat::Nu => Some(ShellType::Nu),
+        ExportFormat::Posix => Some(ShellType::Posix),
+        ExportFormat::Json | ExportFormat::Toml => None,
+    }
+}
+
+/// Apply the tag and enabled-state filters to variables. Name patterns and
+/// alias kinds select aliases, so they leave variables out.
+fn selected_variables<'a>(
+    catalog: &'a AliasCatalog,
+    selection: &AliasSelection,
+) -> Vec<(&'a str, &'a Variable)> {
+    if selection.pattern.is_some() || selection.global || selection.suffix {
+        return Vec::new();
+    }
+    catalog
+        .variables
+        .iter()
+        .filter(|(_, variable)| selection.tag.iter().all(|tag| variable.tags.contains(tag)))
+        .filter(|(_, variable)| selection.all || variable.enabled != selection.disabled)
+        .map(|(name, variable)| (name.as_str(), variable))
+        .collect()
+}
+
+fn format_export(catalog: &AliasCatalog, cmd: &ExportCommand) -> Result<String, Failure> {
+    let shell = export_shell(cmd.format);
+    let aliases = selected_aliases(catalog, &cmd.selection, shell.as_ref())?;
+    let variables = selected_variables(catalog, &cmd.selection);
+    Ok(match (cmd.format, shell) {
+        (_, Some(shell)) => {
+            let export = export_script(&aliases, &variables, &shell, cmd.descriptions);
+            for name in export.skipped {
+                warn!("Skipped alias '{name}', which cannot be defined in {shell}.");
+            }
+            for name in export.skipped_variables {
+                warn!("Skipped variable '{name}', which is not a valid variable name.");
+            }
+            export.script
+        }
+        (ExportFormat::Json, None) => format_json(&aliases, catalog),
+        (_, None) => {
+            let mut selected = AliasCatalog::new();
+            for (name, alias) in aliases {
+                selected.aliases.insert(name.to_owned(), alias.clone());
+            }
+            for (name, variable) in variables {
+                selected.variables.insert(name.to_owned(), variable.clone());
+            }
+            render_catalog(&mut selected)
+        }
+    })
+}
+
+pub fn handle_export(catalog: &AliasCatalog, cmd: ExportCommand) -> Result<Outcome, Failure> {
+    print!("{}", format_export(catalog, &cmd)?);
+    Ok(Outcome::NoChanges)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::types::{Alias, AliasKind};
+
+    fn command(format: ExportFormat, all: bool) -> ExportCommand {
+        ExportCommand {
+            selection: AliasSelection {
+                pattern: None,
+                tag: vec![],
+                disabled: false,
+                all,
+                global: false,
+                suffix: false,
+            },
+            format,
+            descriptions: false,
+        }
+    }
+
+    fn catalog() -> AliasCatalog {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert(
+            "G".into(),
+            Alias::new("| grep".into(), true, AliasKind::Global),
+        );
+        catalog.aliases.insert(
+            "gs".into(),
+            Alias::new("git status".into(), false, AliasKind::Regular),
+        );
+        catalog.aliases.insert(
+            "ll".into(),
+            Alias::new("ls -la".into(), true, AliasKind::Regular),
+        );
+        catalog
+    }
+
+    #[test]
+    fn shell_exports_follow_the_selection_and_target_shell() {
+        let bash = format_export(&catalog(), &command(ExportFormat::Bash, false)).unwrap();
+        assert_eq!(
+            bash,
+            "# Aliases exported by aliasmgr for BASH\nalias -- 'll=ls -la'\n"
+        );
+
+        let zsh = format_export(&catalog(), &command(ExportFormat::Zsh, true)).unwrap();
+        assert!(zsh.contains("\nalias -g -- 'G=| grep'\n"));
+        assert!(zsh.contains("\n# alias -- 'gs=git status'\n"));
+    }
+
+    #[test]
+    fn exports_include_selected_variables() {
+        let mut catalog = catalog();
+        catalog
+            .variables
+            .insert("EDITOR".into(), Variable::new("nvim".into(), true));
+        let mut gopath = Variable::new("/home/me/go".into(), false);
+        gopath.tags.insert("go".into());
+        catalog.variables.insert("GOPATH".into(), gopath);
+
+        let bash = format_export(&catalog, &command(ExportFormat::Bash, false)).unwrap();
+        assert!(bash.ends_with("alias -- 'll=ls -la'\nexport EDITOR='nvim'\n"));
+        let fish = format_export(&catalog, &command(ExportFormat::Fish, true)).unwrap();
+        assert!(fish.ends_with("set -gx EDITOR 'nvim'\n# set -gx GOPATH '/home/me/go'\n"));
+
+        let toml = format_export(&catalog, &command(ExportFormat::Toml, true)).unwrap();
+        assert!(toml.ends_with(concat!(
+            "ll = \"ls -la\"\n",
+            "\n",
+            "[env]\n",
+            "EDITOR = \"nvim\"\n",
+            "GOPATH = { value = \"/home/me/go\", enabled = false, tags = [\"go\"] }\n",
+        )));
+
+        let mut tagged = command(ExportFormat::Toml, true);
+        tagged.selection.tag = vec!["go".into()];
+        assert!(!format_export(&catalog, &tagged).unwrap().contains("EDITOR"));
+        let mut named = command(ExportFormat::Bash, true);
+        named.selection.pattern = Some("l*".into());
+        assert!(!format_export(&catalog, &named).unwrap().contains("EDITOR"));
+    }
+
+    #[test]
+    fn data_exports_keep_global_aliases_and_metadata() {
+        let toml = format_export(&catalog(), &command(ExportFormat::Toml, true)).unwrap();
+        assert_eq!(
+            toml,
+            concat!(
+                "version = 2\n",
+                "\n",
+                "G = { command = \"| grep\", enabled = true, global = true }\n",
+                "gs = { command = \"git status\", enabled = false, global = false }\n",
+                "ll = \"ls -la\"\n",
+            )
+        );
+
+        let json = format_export(&catalog(), &command(ExportFormat::Json, false)).unwrap();
+        assert!(json.contains("\"name\": \"G\""));
+        assert!(!json.contains("\"name\": \"gs\""));
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{error, warn};
+
+use super::CommandOutcome;
+use super::shell::shell_quote;
+use crate::catalog::io::{parse_catalog, restore_catalog_content};
+use crate::catalog::journal::{Journal, JournalEntry, journal_path, load_journal, save_journal};
+use crate::core::history::describe_changes;
+use crate::core::{Failure, Outcome};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn format_age(seconds: u64) -> String {
+    let (value, unit) = match seconds {
+        0..60 => return "just now".into(),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86400 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    format!("{value} {unit}{} ago", if value == 1 { "" } else { "s" })
+}
+
+/// Render command-line arguments as a shell line, quoting only where needed.
+pub fn describe_invocation(arguments: impl IntoIterator<Item = String>) -> String {
+    arguments
+        .into_iter()
+        .map(|argument| {
+            let plain = !argument.is_empty()
+                && argument.chars().all(|character| {
+                    character.is_ascii_alphanumeric() || "-_./=:,@%+".contains(character)
+                });
+            if plain {
+                argument
+            } else {
+                shell_quote(&argument)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Record a saved change in the journal. Failures are reported but do not
+/// fail the command, because the catalog itself was already saved.
+pub fn record_catalog_change(catalog_path: &Path, command: String, before: &str, after: &str) {
+    if before == after {
+        return;
+    }
+    let path = journal_path(catalog_path);
+    let result = load_journal(&path).and_then(|mut journal| {
+        journal.record(JournalEntry {
+            timestamp: now(),
+            command,
+            before: before.into(),
+            after: after.into(),
+        });
+        save_journal(&path, &journal)
+    });
+    if let Err(error) = result {
+        warn!("Could not record the change for undo: {error:#}");
+    }
+}
+
+fn load_history(catalog_path: &Path) -> Result<(PathBuf, Journal), Failure> {
+    let path = journal_path(catalog_path);
+    let journal = load_journal(&path).map_err(|load_error| {
+        error!("{load_error:#}");
+        Failure::HistoryUnavailable
+    })?;
+    Ok((path, journal))
+}
+
+fn summarize(before: &str, after: &str) -> Vec<String> {
+    match (parse_catalog(before), parse_catalog(after)) {
+        (Ok(before), Ok(after)) => describe_changes(&before, &after),
+        _ => vec!["catalog text changed".into()],
+    }
+}
+
+fn step_message(action: &str, command: &str, from: &str, to: &str) -> String {
+    let mut lines = vec![format!("{action}: {command}")];
+    lines.extend(
+        summarize(from, to)
+            .into_iter()
+            .map(|line| format!("  {line}")),
+    );
+    lines.join("\n")
+}
+
+fn replace_catalog(
+    catalog_path: &Path,
+    loaded_content: &str,
+    loaded_stamp: &str,
+    expected: &str,
+    replacement: &str,
+) -> Result<(), Failure> {
+    if loaded_content != expected {
+        return Err(Failure::HistoryOutOfDate);
+    }
+    restore_catalog_content(catalog_path, replacement, loaded_stamp).map_err(|write_error| {
+        error!("{write_error:#}");
+        Failure::CatalogWriteFailed
+    })
+}
+
+fn save_history(path: &Path, journal: &Journal) -> Result<(), Failure> {
+    save_journal(path, journal).map_err(|save_error| {
+        error!("{save_error:#}");
+        Failure::HistoryUnavailable
+    })
+}
+
+pub fn handle_undo(
+    catalog_path: &Path,
+    loaded_content: &str,
+    loaded_stamp: &str,
+) -> Result<CommandOutcome, Failure> {
+    let (path, mut journal) = load_history(catalog_path)?;
+    let entry = journal.undo_entry().ok_or(Failure::NothingToUndo)?.clone();
+    replace_catalog(
+        catalog_path,
+        loaded_content,
+        loaded_stamp,
+        &entry.after,
+        &entry.before,
+    )?;
+    journal.cursor -= 1;
+    save_history(&path, &journal)?;
+    Ok(CommandOutcome::with_message(
+        Outcome::NoChanges,
+        step_message("Undid", &entry.command, &entry.after, &entry.before),
+    ))
+}
+
+pub fn handle_redo(
+    catalog_path: &Path,
+    loaded_content: &str,
+    loaded_stamp: &str,
+) -> Result<CommandOutcome, Failure> {
+    let (path, mut journal) = load_history(catalog_path)?;
+    let entry = journal.redo_entry().ok_or(Failure::NothingToRedo)?.clone();
+    replace_catalog(
+        catalog_path,
+        loaded_content,
+        loaded_stamp,
+        &entry.before,
+        &entry.after,
+    )?;
+    journal.cursor += 1;
+    save_history(&path, &journal)?;
+    Ok(CommandOutcome::with_message(
+        Outcome::NoChanges,
+        step_message("Redid", &entry.command, &entry.before, &entry.after),
+    ))
+}
+
+fn format_history(journal: &Journal, now: u64) -> String {
+    if journal.entries.is_empty() {
+        return "No catalog changes have been recorded.".into();
+    }
+    let mut lines = Vec::new();
+    for (index, entry) in journal.entries.iter().enumerate().rev() {
+        let undone = if index >= journal.cursor {
+            " (undone)"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "{}. {}{undone}: {}",
+            index + 1,
+            format_age(now.saturating_sub(entry.timestamp)),
+            entry.command
+        ));
+        lines.extend(
+            summarize(&entry.before, &entry.after)
+                .into_iter()
+                .map(|line| format!("   {line}")),
+        );
+    }
+    lines.join("\n")
+}
+
+pub fn handle_history(catalog_path: &Path) -> Result<CommandOutcome, Failure> {
+    let (_, journal) = load_history(catalog_path)?;
+    Ok(CommandOutcome::with_message(
+        Outcome::NoChanges,
+        format_history(&journal, now()),
+    ))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::io::catalog_stamp;
+    use std::fs;
+
+    #[test]
+    fn invocations_quote_only_arguments_that_need_it() {
+        assert_eq!(
+            describe_invocation(["add", "ll", "ls -la", "--tag=files"].map(String::from)),
+            "add ll 'ls -la' --tag=files"
+        );
+    }
+
+    #[test]
+    fn ages_are_rounded_down_to_the_largest_unit() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(60), "1 minute ago");
+        assert_eq!(format_age(7200), "2 hours ago");
+        assert_eq!(format_age(3 * 86400), "3 days ago");
+    }
+
+    #[test]
+    fn undo_and_redo_restore_recorded_catalog_text() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog = directory.path().join("aliases.toml");
+        let before = "# mine\nll = \"ls\"\n";
+        let after = "# mine\nll = \"ls\"\ngs = \"git status\"\n";
+        fs::write(&catalog, after).unwrap();
+        record_catalog_change(&catalog, "add gs 'git status'".into(), before, after);
+
+        let undo = handle_undo(&catalog, after, &catalog_stamp(&catalog)).unwrap();
+        assert_eq!(
+            undo.message.unwrap(),
+            "Undid: add gs 'git status'\n  - gs: git status"
+        );
+        assert_eq!(fs::read_to_string(&catalog).unwrap(), before);
+        assert_eq!(
+            handle_undo(&catalog, before, &catalog_stamp(&catalog)),
+            Err(Failure::NothingToUndo)
+        );
+
+        handle_redo(&catalog, before, &catalog_stamp(&catalog)).unwrap();
+        assert_eq!(fs::read_to_string(&catalog).unwrap(), after);
+        assert_eq!(
+            handle_redo(&catalog, after, &catalog_stamp(&catalog)),
+            Err(Failure::NothingToRedo)
+        );
+    }
+
+    #[test]
+    fn undo_refuses_to_discard_unrecorded_edits() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog = directory.path().join("aliases.toml");
+        record_catalog_change(&catalog, "add ll ls".into(), "", "ll = \"ls\"\n");
+        let edited = "ll = \"ls\"\nmanual = \"edit\"\n";
+        fs::write(&catalog, edited).unwrap();
+
+        assert_eq!(
+            handle_undo(&catalog, edited, &catalog_stamp(&catalog)),
+            Err(Failure::HistoryOutOfDate)
+        );
+        assert_eq!(fs::read_to_string(&catalog).unwrap(), edited);
+    }
+
+    #[test]
+    fn history_lists_newest_first_and_marks_undone_entries() {
+        let mut journal = Journal::default();
+        for (timestamp, command, before, after) in [
+            (0, "add ll ls", "", "ll = \"ls\"\n"),
+            (
+                3600,
+                "disable ll",
+                "ll = \"ls\"\n",
+                "ll = { command = \"ls\", enabled = false }\n",
+            ),
+        ] {
+            journal.record(JournalEntry {
+                timestamp,
+                command: command.into(),
+                before: before.into(),
+                after: after.into(),
+            });
+        }
+        journal.cursor = 1;
+
+        assert_eq!(
+            format_history(&journal, 7200),
+            concat!(
+                "2. 1 hour ago (undone): disable ll\n",
+                "   ~ ll: disabled\n",
+                "1. 2 hours ago: add ll ls\n",
+                "   + ll: ls",
+            )
+        );
+        assert_eq!(
+            format_history(&Journal::default(), 0),
+            "No catalog changes have been recorded."
+        );
+    }
+}
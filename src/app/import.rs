@@ -1,11 +1,19 @@
 use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
 
-use log::warn;
+use log::{error, info, warn};
+use serde::Serialize;
 
 use crate::catalog::types::{Alias, AliasCatalog};
-use crate::cli::import::ImportCommand;
+use crate::cli::import::{AliasListingShell, ImportCommand, ImportFormat};
 use crate::cli::interaction::{InteractionMode, prompt_replace_imported_alias};
-use crate::core::import::{ParsedLine, is_identical, parse_alias_line};
+use crate::cli::list::OutputFormat;
+use crate::core::import::{
+    ImportRecord, ParsedLine, is_identical, is_same_alias, logical_lines, looks_like_json,
+    merge_alias, parse_alias_line, parse_json_aliases, section_tag, side_by_side,
+};
 use crate::core::{Failure, Outcome};
 
 use super::CommandOutcome;
@@ -15,6 +23,74 @@ enum CollisionPolicy {
     Prompt,
     Skip,
     Replace,
+    Merge,
+}
+
+/// What happened to one imported line or JSON record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ImportResult {
+    Imported,
+    Replaced,
+    Merged,
+    /// Imported under a new name because the original name was taken.
+    Renamed,
+    SkippedCollision,
+    /// A dry-run collision that would be decided by a prompt.
+    Collision,
+    Unchanged,
+    Unsupported,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Position {
+    Line(usize),
+    Record(usize),
+}
+
+#[derive(Debug, Serialize)]
+struct ReportEntry {
+    source: String,
+    #[serde(flatten)]
+    position: Position,
+    name: Option<String>,
+    result: ImportResult,
+    reason: Option<String>,
+}
+
+impl ReportEntry {
+    fn describe(&self) -> String {
+        let location = match self.position {
+            Position::Line(line) => format!("{}:{line}", self.source),
+            Position::Record(record) => format!("{}: record {record}", self.source),
+        };
+        let name = self.name.as_deref().unwrap_or_default();
+        match self.result {
+            ImportResult::Imported => format!("{location}: imported '{name}'."),
+            ImportResult::Replaced => format!("{location}: replaced '{name}'."),
+            ImportResult::Merged => format!("{location}: merged '{name}' into the catalog's."),
+            ImportResult::Renamed => format!(
+                "{location}: '{name}' {}.",
+                self.reason.as_deref().unwrap_or_default()
+            ),
+            ImportResult::SkippedCollision => {
+                format!("{location}: kept the catalog's '{name}'.")
+            }
+            ImportResult::Collision => format!("{location}: '{name}' collides with the catalog."),
+            ImportResult::Unchanged => format!("{location}: '{name}' is unchanged."),
+            ImportResult::Unsupported => {
+                let skipped = match self.position {
+                    Position::Line(_) => "line",
+                    Position::Record(_) => "record",
+                };
+                format!(
+                    "{location}: {}; skipping {skipped}.",
+                    self.reason.as_deref().unwrap_or_default()
+                )
+            }
+        }
+    }
 }
 
 #[derive(Default)]
@@ -22,9 +98,43 @@ struct ImportSummary {
     imported: usize,
     collisions: usize,
     replaced: usize,
+    merged: usize,
+    renamed: usize,
     skipped_collisions: usize,
-    unsupported: usize,
+    unsupported_lines: usize,
+    unsupported_entries: usize,
     unchanged: usize,
+    entries: Vec<ReportEntry>,
+}
+
+impl ImportSummary {
+    fn record(&mut self, entry: ReportEntry) {
+        match entry.result {
+            ImportResult::Imported => self.imported += 1,
+            ImportResult::Replaced => self.replaced += 1,
+            ImportResult::Merged => self.merged += 1,
+            ImportResult::Renamed => self.renamed += 1,
+            ImportResult::SkippedCollision => self.skipped_collisions += 1,
+            ImportResult::Collision => {}
+            ImportResult::Unchanged => self.unchanged += 1,
+            ImportResult::Unsupported => match entry.position {
+                Position::Line(_) => self.unsupported_lines += 1,
+                Position::Record(_) => self.unsupported_entries += 1,
+            },
+        }
+        if matches!(
+            entry.result,
+            ImportResult::Replaced
+                | ImportResult::Merged
+                | ImportResult::Renamed
+                | ImportResult::SkippedCollision
+                | ImportResult::Collision
+        ) {
+            self.collisions += 1;
+        }
+        info!("{}", entry.describe());
+        self.entries.push(entry);
+    }
 }
 
 pub fn handle_import(
@@ -32,7 +142,9 @@ pub fn handle_import(
     args: ImportCommand,
     interaction_mode: InteractionMode,
 ) -> Result<CommandOutcome, Failure> {
-    let policy = if args.replace_existing || interaction_mode == InteractionMode::Yes {
+    let policy = if args.merge_existing {
+        CollisionPolicy::Merge
+    } else if args.replace_existing || interaction_mode == InteractionMode::Yes {
         CollisionPolicy::Replace
     } else if args.skip_existing || interaction_mode == InteractionMode::No {
         CollisionPolicy::Skip
@@ -42,65 +154,132 @@ pub fn handle_import(
     let mut candidate = catalog.clone();
     let mut summary = ImportSummary::default();
 
+    let mut sources = Vec::new();
+    if let Some(shell) = args.from_shell {
+        let content = live_shell_aliases(shell).map_err(|listing_error| {
+            error!("{listing_error}");
+            Failure::ShellAliasesUnavailable
+        })?;
+        sources.push((source_label(shell), content));
+    }
     for path in &args.paths {
-        let content = match fs::read_to_string(path) {
-            Ok(content) => content,
+        match read_source(path) {
+            Ok(content) if path == Path::new("-") => sources.push(("<stdin>".into(), content)),
+            Ok(content) => sources.push((path.display().to_string(), content)),
             Err(error) => {
                 warn!(
                     "Could not import '{}': {error}; skipping file.",
                     path.display()
                 );
-                continue;
             }
+        }
+    }
+
+    for (label, content) in &sources {
+        let json = match args.format {
+            ImportFormat::Auto => looks_like_json(content),
+            ImportFormat::Shell => false,
+            ImportFormat::Json => true,
+        };
+        let records = if json {
+            match parse_json_aliases(content) {
+                Ok(records) => records.into_iter().enumerate().collect(),
+                Err(error) => {
+                    warn!("Could not import '{label}': {error}; skipping file.");
+                    continue;
+                }
+            }
+        } else {
+            shell_records(content, &args)
         };
 
-        for line in content.lines() {
-            let (name, command, global) = match parse_alias_line(line) {
-                ParsedLine::Alias {
-                    name,
-                    command,
-                    global,
-                } => (name, command, global),
-                ParsedLine::Unsupported => {
-                    summary.unsupported += 1;
+        for (index, record) in records {
+            let position = if json {
+                Position::Record(index + 1)
+            } else {
+                Position::Line(index + 1)
+            };
+            let mut entry = ReportEntry {
+                source: label.clone(),
+                position,
+                name: None,
+                result: ImportResult::Imported,
+                reason: None,
+            };
+            let (mut name, mut alias) = match record {
+                Ok(record) => record,
+                Err(reason) => {
+                    entry.result = ImportResult::Unsupported;
+                    entry.reason = Some(reason);
+                    summary.record(entry);
                     continue;
                 }
-                ParsedLine::Ignored => continue,
+            };
+            entry.name = Some(name.clone());
+
+            alias.tags.extend(args.tag.iter().cloned());
+            let same = |existing: &Alias, alias: &Alias| {
+                if json {
+                    is_same_alias(existing, alias)
+                } else {
+                    is_identical(existing, &alias.command, alias.kind)
+                }
             };
 
             if let Some(existing) = candidate.aliases.get(&name) {
-                if is_identical(existing, &command, global) {
-                    summary.unchanged += 1;
+                if same(existing, &alias) {
+                    entry.result = ImportResult::Unchanged;
+                    summary.record(entry);
                     continue;
                 }
 
-                summary.collisions += 1;
                 let replace = match policy {
                     CollisionPolicy::Replace => true,
-                    CollisionPolicy::Skip => false,
+                    CollisionPolicy::Skip | CollisionPolicy::Merge => false,
                     CollisionPolicy::Prompt if args.dry_run => false,
-                    CollisionPolicy::Prompt => {
-                        prompt_replace_imported_alias(interaction_mode, &name)
-                    }
+                    CollisionPolicy::Prompt => prompt_replace_imported_alias(
+                        interaction_mode,
+                        &name,
+                        &side_by_side(existing, &alias).join("\n"),
+                    ),
                 };
-                if !replace {
-                    if !args.dry_run || matches!(policy, CollisionPolicy::Skip) {
-                        summary.skipped_collisions += 1;
+                let undecided = args.dry_run && matches!(policy, CollisionPolicy::Prompt);
+                if matches!(policy, CollisionPolicy::Merge) {
+                    alias = merge_alias(existing, &alias);
+                    if is_same_alias(existing, &alias) {
+                        entry.result = ImportResult::Unchanged;
+                        summary.record(entry);
+                        continue;
                     }
+                    entry.result = ImportResult::Merged;
+                } else if replace {
+                    entry.result = ImportResult::Replaced;
+                } else if let Some(suffix) = args.rename_suffix.as_deref().filter(|_| !undecided) {
+                    let Some(renamed) = free_name(&candidate, &name, suffix, &alias, same) else {
+                        entry.result = ImportResult::Unchanged;
+                        summary.record(entry);
+                        continue;
+                    };
+                    entry.result = ImportResult::Renamed;
+                    entry.reason = Some(format!("imported as '{renamed}'"));
+                    name = renamed;
+                } else {
+                    entry.result = if undecided {
+                        ImportResult::Collision
+                    } else {
+                        ImportResult::SkippedCollision
+                    };
+                    entry.reason = Some("an alias with this name is already in the catalog".into());
+                    summary.record(entry);
                     continue;
                 }
-                summary.replaced += 1;
-            } else {
-                summary.imported += 1;
             }
-
-            let mut alias = Alias::new(command, true, global);
-            alias.tags.extend(args.tag.iter().cloned());
+            summary.record(entry);
             candidate.aliases.insert(name, alias);
         }
     }
 
-    let changed = summary.imported + summary.replaced > 0;
+    let changed = summary.imported + summary.replaced + summary.merged + summary.renamed > 0;
     if changed && !args.dry_run {
         *catalog = candidate;
     }
@@ -109,10 +288,117 @@ pub fn handle_import(
     } else {
         Outcome::NoChanges
     };
-    Ok(CommandOutcome::with_message(
-        outcome,
-        format_summary(&summary, args.dry_run, policy),
-    ))
+    let message = match args.output_format {
+        OutputFormat::Human => format_summary(&summary, args.dry_run, policy),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(&summary.entries).expect("import report serializes")
+        }
+    };
+    Ok(CommandOutcome::with_message(outcome, message))
+}
+
+/// Find a name for an imported alias whose own name is taken, by appending
+/// `suffix` and then a counter. Returns `None` when an identical alias already
+/// uses one of those names, as after importing the same file twice.
+fn free_name(
+    catalog: &AliasCatalog,
+    name: &str,
+    suffix: &str,
+    alias: &Alias,
+    same: impl Fn(&Alias, &Alias) -> bool,
+) -> Option<String> {
+    (1..)
+        .map(|attempt| match attempt {
+            1 => format!("{name}{suffix}"),
+            _ => format!("{name}{suffix}{attempt}"),
+        })
+        .find_map(|candidate| match catalog.aliases.get(&candidate) {
+            None => Some(Some(candidate)),
+            Some(existing) if same(existing, alias) => Some(None),
+            Some(_) => None,
+        })
+        .flatten()
+}
+
+/// Parse shell declarations into each line's alias, or the reason the line
+/// cannot be imported. Blank and comment lines are left out.
+fn shell_records(content: &str, args: &ImportCommand) -> Vec<(usize, ImportRecord)> {
+    let mut records = Vec::new();
+    let mut section = None;
+    for (index, line) in logical_lines(content) {
+        let record = match parse_alias_line(&line) {
+            ParsedLine::Alias {
+                name,
+                command,
+                kind,
+                comment,
+            } => {
+                let mut alias = Alias::new(command, true, kind);
+                if args.comment_descriptions {
+                    alias.description = comment;
+                }
+                if args.section_tags {
+                    alias.tags.extend(section.clone());
+                }
+                Ok((name, alias))
+            }
+            ParsedLine::Comment(comment) => {
+                if let Some(tag) = section_tag(&comment) {
+                    section = Some(tag);
+                }
+                continue;
+            }
+            ParsedLine::Unsupported(reason) => Err(reason),
+            ParsedLine::Ignored => continue,
+        };
+        records.push((index, record));
+    }
+    records
+}
+
+fn read_source(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    fs::read_to_string(path)
+}
+
+fn source_label(shell: AliasListingShell) -> String {
+    match shell {
+        AliasListingShell::Bash => "<bash>".into(),
+        AliasListingShell::Zsh => "<zsh>".into(),
+    }
+}
+
+/// Separates the listing from anything that startup files print.
+const LISTING_MARKER: &str = "__aliasmgr_alias_listing__";
+
+/// List the aliases of a new interactive shell, so aliases defined by startup
+/// files and frameworks such as oh-my-zsh are included.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn live_shell_aliases(shell: AliasListingShell) -> io::Result<String> {
+    let (program, listing) = match shell {
+        AliasListingShell::Bash => ("bash", "alias -p"),
+        AliasListingShell::Zsh => ("zsh", "alias -L"),
+    };
+    let output = Command::new(program)
+        .args(["-i", "-c", &format!("echo {LISTING_MARKER}; {listing}")])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|error| {
+            io::Error::new(error.kind(), format!("could not run {program}: {error}"))
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.split_once(&format!("{LISTING_MARKER}\n")) {
+        Some((_, aliases)) if output.status.success() => Ok(aliases.to_owned()),
+        _ => Err(io::Error::other(format!(
+            "{program} did not list its aliases ({})",
+            output.status
+        ))),
+    }
 }
 
 fn format_summary(summary: &ImportSummary, dry_run: bool, policy: CollisionPolicy) -> String {
@@ -129,13 +415,25 @@ fn format_summary(summary: &ImportSummary, dry_run: bool, policy: CollisionPolic
         let collision_result = if dry_run {
             match policy {
                 CollisionPolicy::Prompt => String::new(),
+                CollisionPolicy::Skip if summary.renamed > 0 => {
+                    " and would be imported under new names".into()
+                }
                 CollisionPolicy::Skip => " and would be skipped".into(),
                 CollisionPolicy::Replace => " and would be replaced".into(),
+                CollisionPolicy::Merge => " and would be merged".into(),
             }
         } else {
+            let mut results = vec![format!("{} replaced", summary.replaced)];
+            if summary.merged > 0 {
+                results.push(format!("{} merged", summary.merged));
+            }
+            if summary.renamed > 0 {
+                results.push(format!("{} renamed", summary.renamed));
+            }
             format!(
-                "; {} replaced and {} skipped",
-                summary.replaced, summary.skipped_collisions
+                "; {} and {} skipped",
+                results.join(", "),
+                summary.skipped_collisions
             )
         };
         parts.push(format!(
@@ -146,8 +444,17 @@ fn format_summary(summary: &ImportSummary, dry_run: bool, policy: CollisionPolic
     if summary.unchanged > 0 {
         parts.push(format!("{} aliases unchanged", summary.unchanged));
     }
-    if summary.unsupported > 0 {
-        parts.push(format!("{} unsupported lines skipped", summary.unsupported));
+    if summary.unsupported_lines > 0 {
+        parts.push(format!(
+            "{} unsupported lines skipped",
+            summary.unsupported_lines
+        ));
+    }
+    if summary.unsupported_entries > 0 {
+        parts.push(format!(
+            "{} unsupported entries skipped",
+            summary.unsupported_entries
+        ));
     }
     format!("{}.", parts.join("; "))
 }
@@ -1,11 +1,12 @@
 use super::file_path::CATALOG_FILE_ENV_VAR;
-use super::shell::{SHELL_ENV_VAR, ShellType, shell_quote};
+use super::shell::{SHELL_ENV_VAR, ShellType, fish_quote, nu_quote, shell_quote};
 use crate::cli::init::InitCommand;
 use crate::config::CONFIG_FILE_ENV_VAR;
 
 const COMMON_SHELL_FUNCTIONS: &str = r#"
 : "${__aliasmgr_managed_aliases:=}"
 : "${__aliasmgr_catalog_revision:=}"
+: "${__aliasmgr_catalog_stamp:=}"
 : "${__aliasmgr_sync_in_progress:=0}"
 
 __aliasmgr_apply_sync() {
@@ -16,6 +17,7 @@ __aliasmgr_apply_sync() {
     changes="$(
         ALIASMGR_MANAGED_ALIASES="$__aliasmgr_managed_aliases" \
         ALIASMGR_CATALOG_REVISION="$__aliasmgr_catalog_revision" \
+        ALIASMGR_CATALOG_STAMP="$__aliasmgr_catalog_stamp" \
         "$__aliasmgr_cmd" shell-sync "$mode"
     )"
     sync_status=$?
@@ -40,7 +42,7 @@ aliasmgr() {
         return $?
     fi
 
-    "$__aliasmgr_cmd" "$@"
+    ALIASMGR_CATALOG_REVISION="$__aliasmgr_catalog_revision" "$__aliasmgr_cmd" "$@"
 }
 
 __aliasmgr_prompt_sync() {
@@ -88,43 +90,220 @@ add-zsh-hook precmd __aliasmgr_prompt_sync
 precmd_functions=(__aliasmgr_prompt_sync ${precmd_functions:#__aliasmgr_prompt_sync})
 "#;
 
-fn helper_shell_command(shell: &ShellType) -> &'static str {
+// POSIX shells have neither `local` nor a prompt hook, so positional
+// parameters hold temporaries and aliasmgr invocations trigger the sync.
+const POSIX_SHELL_FUNCTIONS: &str = r#"
+: "${__aliasmgr_managed_aliases:=}"
+: "${__aliasmgr_catalog_revision:=}"
+: "${__aliasmgr_catalog_stamp:=}"
+
+__aliasmgr_apply_sync() {
+    __aliasmgr_changes="$(
+        ALIASMGR_MANAGED_ALIASES="$__aliasmgr_managed_aliases" \
+        ALIASMGR_CATALOG_REVISION="$__aliasmgr_catalog_revision" \
+        ALIASMGR_CATALOG_STAMP="$__aliasmgr_catalog_stamp" \
+        "$__aliasmgr_cmd" shell-sync "$1"
+    )"
+    set -- "$?" "$__aliasmgr_changes"
+    unset __aliasmgr_changes
+
+    if [ "$1" -ne 0 ]; then
+        return "$1"
+    fi
+
+    if [ -n "$2" ]; then
+        eval "$2"
+    fi
+}
+
+__aliasmgr_command_hook() {
+    :
+}
+
+aliasmgr() {
+    if [ "$#" -gt 0 ] && [ "$1" = "sync" ]; then
+        shift
+        if [ "$#" -ne 0 ]; then
+            "$__aliasmgr_cmd" sync "$@"
+            return $?
+        fi
+        __aliasmgr_apply_sync --force
+        return $?
+    fi
+
+    ALIASMGR_CATALOG_REVISION="$__aliasmgr_catalog_revision" "$__aliasmgr_cmd" "$@"
+    set -- "$?"
+    __aliasmgr_command_hook
+    return "$1"
+}
+"#;
+
+const POSIX_COMMAND_HOOK: &str = r#"
+__aliasmgr_command_hook() {
+    __aliasmgr_apply_sync --if-changed || :
+}
+"#;
+
+const FISH_SHELL_FUNCTIONS: &str = r#"
+set -q __aliasmgr_managed_aliases; or set -g __aliasmgr_managed_aliases ''
+set -q __aliasmgr_catalog_revision; or set -g __aliasmgr_catalog_revision ''
+set -q __aliasmgr_catalog_stamp; or set -g __aliasmgr_catalog_stamp ''
+set -q __aliasmgr_sync_in_progress; or set -g __aliasmgr_sync_in_progress 0
+
+function __aliasmgr_apply_sync
+    set -l changes (
+        ALIASMGR_MANAGED_ALIASES=$__aliasmgr_managed_aliases \
+        ALIASMGR_CATALOG_REVISION=$__aliasmgr_catalog_revision \
+        ALIASMGR_CATALOG_STAMP=$__aliasmgr_catalog_stamp \
+        $__aliasmgr_cmd shell-sync $argv[1]
+    )
+    set -l sync_status $status
+
+    if test $sync_status -ne 0
+        return $sync_status
+    end
+
+    if test (count $changes) -gt 0
+        string join \n -- $changes | source
+    end
+end
+
+function aliasmgr
+    if test (count $argv) -gt 0; and test "$argv[1]" = sync
+        if test (count $argv) -ne 1
+            $__aliasmgr_cmd $argv
+            return $status
+        end
+        __aliasmgr_apply_sync --force
+        return $status
+    end
+
+    ALIASMGR_CATALOG_REVISION=$__aliasmgr_catalog_revision $__aliasmgr_cmd $argv
+end
+"#;
+
+const FISH_PROMPT_HOOK: &str = r#"
+function __aliasmgr_prompt_sync --on-event fish_prompt
+    set -l previous_status $status
+
+    if test "$__aliasmgr_sync_in_progress" -eq 1
+        return $previous_status
+    end
+
+    set -g __aliasmgr_sync_in_progress 1
+    __aliasmgr_apply_sync --if-changed; or true
+    set -g __aliasmgr_sync_in_progress 0
+    return $previous_status
+end
+"#;
+
+// Nushell cannot evaluate generated text, so `shell-sync` writes a module file
+// that a string hook sources; string hooks are parsed when they run.
+const NU_SHELL_FUNCTIONS: &str = r#"
+$env.ALIASMGR_MANAGED_ALIASES = ""
+$env.ALIASMGR_CATALOG_REVISION = ""
+$env.ALIASMGR_CATALOG_STAMP = ""
+$env.__ALIASMGR_SYNC_FILE = ($nu.temp-dir | path join $"aliasmgr-($nu.pid).nu")
+"" | save --force $env.__ALIASMGR_SYNC_FILE
+
+def __aliasmgr_write_sync [mode: string] {
+    ^$env.__ALIASMGR_CMD shell-sync $mode --output $env.__ALIASMGR_SYNC_FILE
+}
+
+# Aliases from a forced reconciliation are applied before the next prompt
+def "aliasmgr sync" [] {
+    __aliasmgr_write_sync "--force"
+}
+
+$env.config.hooks.pre_prompt = (
+    $env.config.hooks.pre_prompt
+    | append ([
+        $"source ($env.__ALIASMGR_SYNC_FILE | to nuon)"
+        $"'' | save --force ($env.__ALIASMGR_SYNC_FILE | to nuon)"
+    ] | str join "\n")
+)
+"#;
+
+const NU_PROMPT_HOOK: &str = r#"
+$env.config.hooks.pre_prompt = (
+    $env.config.hooks.pre_prompt
+    | prepend {|| __aliasmgr_write_sync "--if-changed" }
+)
+"#;
+
+fn export_variable(shell: &ShellType, name: &str, value: &str) -> String {
     match shell {
-        ShellType::Zsh => "whence -p aliasmgr",
-        ShellType::Bash => "type -P aliasmgr",
+        ShellType::Fish => format!("set -gx {name} {}\n", fish_quote(value)),
+        ShellType::Nu => format!("$env.{name} = {}\n", nu_quote(value)),
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => {
+            format!("export {name}={}\n", shell_quote(value))
+        }
+    }
+}
+
+fn resolve_executable(shell: &ShellType) -> &'static str {
+    match shell {
+        ShellType::Zsh => "__aliasmgr_cmd=$(whence -p aliasmgr)",
+        ShellType::Bash => "__aliasmgr_cmd=$(type -P aliasmgr)",
+        ShellType::Posix => "__aliasmgr_cmd=$(command -v aliasmgr)",
+        ShellType::Fish => "set -g __aliasmgr_cmd (command -s aliasmgr)",
+        ShellType::Nu => {
+            "$env.__ALIASMGR_CMD = (which --all aliasmgr | where type == external | get 0.path)"
+        }
     }
 }
 
 pub fn handle_init(cmd: InitCommand) -> String {
     let mut content = String::from("# Alias Manager Initialization Script\n");
-    content += &format!("export {}={}\n", SHELL_ENV_VAR, cmd.shell);
+    if cmd.shell == ShellType::Nu {
+        content += "# Save this script and source it from config.nu; Nushell cannot evaluate it directly.\n";
+    }
+    content += &export_variable(&cmd.shell, SHELL_ENV_VAR, &cmd.shell.to_string());
     if let Some(catalog_path) = cmd.catalog {
-        content += &format!(
-            "export {}={}\n",
+        content += &export_variable(
+            &cmd.shell,
             CATALOG_FILE_ENV_VAR,
-            shell_quote(&catalog_path.to_string_lossy())
+            &catalog_path.to_string_lossy(),
         );
     }
     if let Some(config_path) = cmd.config {
-        content += &format!(
-            "export {}={}\n",
+        content += &export_variable(
+            &cmd.shell,
             CONFIG_FILE_ENV_VAR,
-            shell_quote(&config_path.to_string_lossy())
+            &config_path.to_string_lossy(),
         );
     }
 
     content += "\n# Resolve the executable before defining the wrapper function\n";
-    content += &format!("__aliasmgr_cmd=$({})\n", helper_shell_command(&cmd.shell));
-    content += COMMON_SHELL_FUNCTIONS;
+    content += resolve_executable(&cmd.shell);
+    content += "\n";
+    content += match cmd.shell {
+        ShellType::Fish => FISH_SHELL_FUNCTIONS,
+        ShellType::Nu => NU_SHELL_FUNCTIONS,
+        ShellType::Posix => POSIX_SHELL_FUNCTIONS,
+        ShellType::Bash | ShellType::Zsh => COMMON_SHELL_FUNCTIONS,
+    };
 
     if !cmd.no_auto_sync {
-        content += "\n# Synchronize when the shell is about to display a prompt\n";
+        content += if cmd.shell == ShellType::Posix {
+            "\n# Synchronize after each aliasmgr command; POSIX shells have no prompt hook\n"
+        } else {
+            "\n# Synchronize when the shell is about to display a prompt\n"
+        };
         content += match cmd.shell {
             ShellType::Bash => BASH_PROMPT_HOOK,
             ShellType::Zsh => ZSH_PROMPT_HOOK,
+            ShellType::Fish => FISH_PROMPT_HOOK,
+            ShellType::Nu => NU_PROMPT_HOOK,
+            ShellType::Posix => POSIX_COMMAND_HOOK,
         };
     }
 
+    if cmd.shell == ShellType::Nu {
+        content += "\n# Load aliases when the first prompt is displayed\n";
+        content += "__aliasmgr_write_sync \"--force\"";
+        return content;
+    }
     content += "\n# Load aliases into this shell\n";
     content += "__aliasmgr_apply_sync --force";
     content
@@ -151,6 +330,7 @@ mod tests {
         assert!(output.contains("__aliasmgr_cmd=$(type -P aliasmgr)"));
         assert!(output.contains("aliasmgr()"));
         assert!(output.contains("shell-sync \"$mode\""));
+        assert!(output.contains("ALIASMGR_CATALOG_STAMP=\"$__aliasmgr_catalog_stamp\""));
         assert!(output.contains("PROMPT_COMMAND"));
         assert!(output.contains("__aliasmgr_apply_sync --force"));
         assert!(!output.contains("3>&1"));
@@ -163,6 +343,63 @@ mod tests {
         assert!(output.contains("add-zsh-hook precmd __aliasmgr_prompt_sync"));
     }
 
+    #[test]
+    fn fish_init_uses_fish_syntax_and_prompt_event() {
+        let output = handle_init(InitCommand {
+            catalog: Some(PathBuf::from("/catalog/it's here.toml")),
+            ..command(ShellType::Fish)
+        });
+        assert!(output.contains("set -gx ALIASMGR_SHELL 'FISH'"));
+        assert!(output.contains(r"set -gx ALIASMGR_CATALOG_PATH '/catalog/it\'s here.toml'"));
+        assert!(output.contains("set -g __aliasmgr_cmd (command -s aliasmgr)"));
+        assert!(output.contains("function __aliasmgr_prompt_sync --on-event fish_prompt"));
+        assert!(output.contains("string join \\n -- $changes | source"));
+        assert!(!output.contains("PROMPT_COMMAND"));
+        assert!(!output.contains("local "));
+        assert!(output.ends_with("__aliasmgr_apply_sync --force"));
+    }
+
+    #[test]
+    fn posix_init_avoids_bashisms_and_syncs_after_commands() {
+        let output = handle_init(command(ShellType::Posix));
+        assert!(output.contains("export ALIASMGR_SHELL='POSIX'"));
+        assert!(output.contains("__aliasmgr_cmd=$(command -v aliasmgr)"));
+        assert!(output.contains("__aliasmgr_apply_sync --if-changed || :"));
+        assert!(!output.contains("local "));
+        assert!(!output.contains("PROMPT_COMMAND"));
+        assert!(!output.contains("[["));
+        assert!(output.ends_with("__aliasmgr_apply_sync --force"));
+
+        let manual = handle_init(InitCommand {
+            no_auto_sync: true,
+            ..command(ShellType::Posix)
+        });
+        assert!(!manual.contains("--if-changed"));
+        assert!(manual.contains("__aliasmgr_command_hook"));
+    }
+
+    #[test]
+    fn nushell_init_sources_a_generated_module_from_prompt_hooks() {
+        let output = handle_init(InitCommand {
+            config: Some(PathBuf::from("/config/\"quoted\".toml")),
+            ..command(ShellType::Nu)
+        });
+        assert!(output.contains("$env.ALIASMGR_SHELL = \"NUSHELL\""));
+        assert!(output.contains(r#"$env.ALIASMGR_CONFIG_PATH = "/config/\"quoted\".toml""#));
+        assert!(output.contains("shell-sync $mode --output $env.__ALIASMGR_SYNC_FILE"));
+        assert!(output.contains("prepend {|| __aliasmgr_write_sync \"--if-changed\" }"));
+        assert!(output.contains("$\"source ($env.__ALIASMGR_SYNC_FILE | to nuon)\""));
+        assert!(output.ends_with("__aliasmgr_write_sync \"--force\""));
+        assert!(!output.contains("eval \""));
+
+        let manual = handle_init(InitCommand {
+            no_auto_sync: true,
+            ..command(ShellType::Nu)
+        });
+        assert!(!manual.contains("--if-changed"));
+        assert!(manual.contains("def \"aliasmgr sync\" []"));
+    }
+
     #[test]
     fn no_auto_sync_keeps_initial_sync_without_prompt_hook() {
         let output = handle_init(InitCommand {
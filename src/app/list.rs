@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::IsTerminal;
 
 use globset::Glob;
@@ -7,10 +8,12 @@ use terminal_size::{Width, terminal_size};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::app::shell::ShellType;
-use crate::catalog::types::{Alias, AliasCatalog};
-use crate::cli::list::{ListColumn, ListCommand, OutputFormat};
+use crate::catalog::types::{Alias, AliasCatalog, AliasKind};
+use crate::cli::list::{AliasSelection, ListColumn, ListCommand, OutputFormat};
 use crate::config::{StatusColumnMode, UserConfig};
 use crate::core::list::visible_aliases;
+use crate::core::profile::TagExpression;
+use crate::core::sync::shell_command;
 use crate::core::{Failure, Outcome};
 
 #[derive(Serialize)]
@@ -19,14 +22,31 @@ struct JsonAlias<'a> {
     command: &'a str,
     enabled: bool,
     global: bool,
-    tags: &'a std::collections::BTreeSet<String>,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    function: bool,
+    tags: &'a BTreeSet<String>,
     description: Option<&'a str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    commands: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    hosts: &'a BTreeSet<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    os: &'a BTreeSet<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    requires: &'a BTreeSet<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layer: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inactive: Option<&'a str>,
 }
 
-fn selected_aliases<'a>(
+/// Apply the selection filters. Without a shell, global aliases are kept
+/// regardless of whether the current shell supports them.
+pub(crate) fn selected_aliases<'a>(
     catalog: &'a AliasCatalog,
-    cmd: &ListCommand,
-    shell: &ShellType,
+    cmd: &AliasSelection,
+    shell: Option<&ShellType>,
 ) -> Result<Vec<(&'a str, &'a Alias)>, Failure> {
     let matcher = cmd
         .pattern
@@ -38,7 +58,12 @@ fn selected_aliases<'a>(
         })
         .transpose()?;
 
-    Ok(visible_aliases(catalog, shell)
+    let candidates = match shell {
+        Some(shell) => visible_aliases(catalog, shell).collect::<Vec<_>>(),
+        None => catalog.aliases.iter().collect(),
+    };
+    Ok(candidates
+        .into_iter()
         .filter(|(name, _)| {
             matcher
                 .as_ref()
@@ -53,7 +78,8 @@ fn selected_aliases<'a>(
                     alias.enabled
                 }
         })
-        .filter(|(_, alias)| !cmd.global || alias.global)
+        .filter(|(_, alias)| !cmd.global || alias.kind == AliasKind::Global)
+        .filter(|(_, alias)| !cmd.suffix || alias.kind == AliasKind::Suffix)
         .map(|(name, alias)| (name.as_str(), alias))
         .collect())
 }
@@ -63,9 +89,11 @@ fn header(column: ListColumn) -> &'static str {
         ListColumn::Status => "Status",
         ListColumn::Name => "Name",
         ListColumn::Command => "Command",
-        ListColumn::Global => "Global",
+        ListColumn::Kind => "Kind",
         ListColumn::Tags => "Tags",
         ListColumn::Description => "Description",
+        ListColumn::Layer => "Layer",
+        ListColumn::Inactive => "Inactive",
     }
 }
 
@@ -79,20 +107,32 @@ fn single_line(value: &str) -> String {
         .collect()
 }
 
-fn raw_cell(column: ListColumn, name: &str, alias: &Alias, config: &UserConfig) -> String {
+fn raw_cell(
+    column: ListColumn,
+    name: &str,
+    alias: &Alias,
+    catalog: &AliasCatalog,
+    shell: &ShellType,
+    config: &UserConfig,
+) -> String {
     match column {
         ListColumn::Status if alias.enabled => config.symbols.enabled.clone(),
         ListColumn::Status => config.symbols.disabled.clone(),
         ListColumn::Name => name.to_owned(),
-        ListColumn::Command => single_line(&alias.command),
-        ListColumn::Global if alias.global => config.symbols.global.clone(),
-        ListColumn::Global => String::new(),
+        ListColumn::Command => single_line(shell_command(alias, shell)),
+        ListColumn::Kind => match alias.kind {
+            AliasKind::Regular => String::new(),
+            AliasKind::Global => config.symbols.global.clone(),
+            AliasKind::Suffix => config.symbols.suffix.clone(),
+        },
         ListColumn::Tags => alias.tags.iter().cloned().collect::<Vec<_>>().join(", "),
         ListColumn::Description => alias
             .description
             .as_deref()
             .map(single_line)
             .unwrap_or_default(),
+        ListColumn::Layer => catalog.layers.get(name).cloned().unwrap_or_default(),
+        ListColumn::Inactive => catalog.inactive.get(name).cloned().unwrap_or_default(),
     }
 }
 
@@ -143,10 +183,12 @@ fn column_widths(
     let separators = columns.len().saturating_sub(1) * 2;
     let shrink_order = [
         ListColumn::Description,
+        ListColumn::Inactive,
+        ListColumn::Layer,
         ListColumn::Command,
         ListColumn::Tags,
         ListColumn::Name,
-        ListColumn::Global,
+        ListColumn::Kind,
         ListColumn::Status,
     ];
 
@@ -187,13 +229,19 @@ fn styled_cell(
     match column {
         ListColumn::Status if alias.enabled => config.styles.enabled.render(raw, colors_enabled),
         ListColumn::Status => config.styles.disabled.render(raw, colors_enabled),
-        ListColumn::Global if alias.global => config.styles.global.render(raw, colors_enabled),
+        ListColumn::Kind => match alias.kind {
+            AliasKind::Regular => raw.to_owned(),
+            AliasKind::Global => config.styles.global.render(raw, colors_enabled),
+            AliasKind::Suffix => config.styles.suffix.render(raw, colors_enabled),
+        },
         _ => raw.to_owned(),
     }
 }
 
 fn format_human(
     aliases: &[(&str, &Alias)],
+    catalog: &AliasCatalog,
+    shell: &ShellType,
     columns: &[ListColumn],
     config: &UserConfig,
     colors_enabled: bool,
@@ -207,7 +255,7 @@ fn format_human(
         .map(|(name, alias)| {
             columns
                 .iter()
-                .map(|column| raw_cell(*column, name, alias, config))
+                .map(|column| raw_cell(*column, name, alias, catalog, shell, config))
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
@@ -245,16 +293,24 @@ fn format_human(
     output
 }
 
-fn format_json(aliases: &[(&str, &Alias)]) -> String {
+pub(crate) fn format_json(aliases: &[(&str, &Alias)], catalog: &AliasCatalog) -> String {
     let aliases = aliases
         .iter()
         .map(|(name, alias)| JsonAlias {
             name,
             command: &alias.command,
             enabled: alias.enabled,
-            global: alias.global,
+            global: alias.kind == AliasKind::Global,
+            kind: alias.kind.as_str(),
+            function: alias.function,
             tags: &alias.tags,
             description: alias.description.as_deref(),
+            commands: &alias.shell_commands,
+            hosts: &alias.conditions.hosts,
+            os: &alias.conditions.os,
+            requires: &alias.conditions.requires,
+            layer: catalog.layers.get(*name).map(String::as_str),
+            inactive: catalog.inactive.get(*name).map(String::as_str),
         })
         .collect::<Vec<_>>();
     serde_json::to_string_pretty(&aliases).expect("alias list serializes") + "\n"
@@ -268,7 +324,7 @@ fn format_list_with_width(
     colors_enabled: bool,
     terminal_width: Option<usize>,
 ) -> Result<String, Failure> {
-    let aliases = selected_aliases(catalog, cmd, shell)?;
+    let aliases = selected_aliases(catalog, &cmd.selection, Some(shell))?;
     if let Some(columns) = &cmd.columns
         && columns
             .iter()
@@ -278,14 +334,14 @@ fn format_list_with_width(
         return Err(Failure::InvalidColumns);
     }
     Ok(match cmd.format {
-        OutputFormat::Json => format_json(&aliases),
+        OutputFormat::Json => format_json(&aliases, catalog),
         OutputFormat::Human => {
             let mut columns = if let Some(columns) = &cmd.columns {
                 columns.clone()
             } else {
                 let mut columns = config.list.columns.clone();
                 let show_status = match config.list.status {
-                    StatusColumnMode::Auto => cmd.all,
+                    StatusColumnMode::Auto => cmd.selection.all,
                     StatusColumnMode::Always => true,
                     StatusColumnMode::Never => false,
                 };
@@ -293,12 +349,29 @@ fn format_list_with_width(
                 if show_status {
                     columns.insert(0, ListColumn::Status);
                 }
+                if !catalog.layers.is_empty() && !columns.contains(&ListColumn::Layer) {
+                    columns.push(ListColumn::Layer);
+                }
+                if aliases
+                    .iter()
+                    .any(|(name, _)| catalog.inactive.contains_key(*name))
+                {
+                    columns.push(ListColumn::Inactive);
+                }
                 columns
             };
-            if *shell == ShellType::Bash {
-                columns.retain(|column| *column != ListColumn::Global);
+            if *shell != ShellType::Zsh {
+                columns.retain(|column| *column != ListColumn::Kind);
             }
-            format_human(&aliases, &columns, config, colors_enabled, terminal_width)
+            format_human(
+                &aliases,
+                catalog,
+                shell,
+                &columns,
+                config,
+                colors_enabled,
+                terminal_width,
+            )
         }
     })
 }
@@ -308,16 +381,20 @@ pub fn handle_list(
     cmd: ListCommand,
     shell: &ShellType,
     config: &UserConfig,
+    profile: Option<(&str, &TagExpression)>,
     colors_enabled: bool,
 ) -> Result<Outcome, Failure> {
     let width = std::io::stdout()
         .is_terminal()
         .then(|| terminal_size().map(|(Width(width), _)| usize::from(width)))
         .flatten();
-    print!(
-        "{}",
-        format_list_with_width(catalog, &cmd, shell, config, colors_enabled, width)?
-    );
+    let output = format_list_with_width(catalog, &cmd, shell, config, colors_enabled, width)?;
+    if let Some((name, expression)) = profile
+        && cmd.format == OutputFormat::Human
+    {
+        println!("Profile: {name} ({expression})");
+    }
+    print!("{output}");
     Ok(Outcome::NoChanges)
 }
 
@@ -329,11 +406,14 @@ mod tests {
 
     fn command(format: OutputFormat) -> ListCommand {
         ListCommand {
-            pattern: None,
-            tag: vec![],
-            disabled: false,
-            all: false,
-            global: false,
+            selection: AliasSelection {
+                pattern: None,
+                tag: vec![],
+                disabled: false,
+                all: false,
+                global: false,
+                suffix: false,
+            },
             format,
             columns: None,
         }
@@ -341,7 +421,7 @@ mod tests {
 
     fn catalog() -> AliasCatalog {
         let mut catalog = AliasCatalog::new();
-        let mut alias = Alias::new("cargo test --workspace".into(), true, false);
+        let mut alias = Alias::new("cargo test --workspace".into(), true, AliasKind::Regular);
         alias.tags.extend(["dev".into(), "rust".into()]);
         alias.description = Some("Run the complete test suite".into());
         catalog.aliases.insert("test".into(), alias);
@@ -362,7 +442,7 @@ mod tests {
         assert!(output.lines().next().unwrap().starts_with("Name  Command"));
         let header = output.lines().next().unwrap();
         assert!(!header.contains("Status"));
-        assert!(!header.contains("Global"));
+        assert!(!header.contains("Kind"));
 
         let output = format_list_with_width(
             &catalog(),
@@ -373,7 +453,82 @@ mod tests {
             None,
         )
         .unwrap();
-        assert!(output.lines().next().unwrap().contains("Global"));
+        assert!(output.lines().next().unwrap().contains("Kind"));
+    }
+
+    #[test]
+    fn layered_catalogs_show_the_source_layer() {
+        let mut catalog = catalog();
+        catalog.layers.insert("test".into(), "system".into());
+
+        let output = format_list_with_width(
+            &catalog,
+            &command(OutputFormat::Human),
+            &ShellType::Bash,
+            &UserConfig::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        let header = output.lines().next().unwrap();
+        assert!(header.ends_with("Layer"));
+        assert!(output.lines().nth(1).unwrap().ends_with("system"));
+
+        let output = format_list_with_width(
+            &catalog,
+            &command(OutputFormat::Json),
+            &ShellType::Bash,
+            &UserConfig::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(output.contains("\"layer\": \"system\""));
+    }
+
+    #[test]
+    fn inactive_aliases_explain_why() {
+        let mut catalog = catalog();
+        catalog
+            .aliases
+            .get_mut("test")
+            .unwrap()
+            .conditions
+            .os
+            .insert("macos".into());
+        catalog
+            .inactive
+            .insert("test".into(), "OS 'linux' is not one of macos".into());
+
+        let output = format_list_with_width(
+            &catalog,
+            &command(OutputFormat::Human),
+            &ShellType::Bash,
+            &UserConfig::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(output.lines().next().unwrap().ends_with("Inactive"));
+        assert!(
+            output
+                .lines()
+                .nth(1)
+                .unwrap()
+                .ends_with("OS 'linux' is not one of macos")
+        );
+
+        let output = format_list_with_width(
+            &catalog,
+            &command(OutputFormat::Json),
+            &ShellType::Bash,
+            &UserConfig::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(output.contains("\"os\": [\n      \"macos\"\n    ]"));
+        assert!(output.contains("\"inactive\": \"OS 'linux' is not one of macos\""));
     }
 
     #[test]
@@ -413,7 +568,7 @@ mod tests {
     #[test]
     fn narrow_tables_truncate_without_dropping_columns() {
         let mut cmd = command(OutputFormat::Human);
-        cmd.all = true;
+        cmd.selection.all = true;
         let output = format_list_with_width(
             &catalog(),
             &cmd,
@@ -432,7 +587,7 @@ mod tests {
         let mut catalog = catalog();
         catalog.aliases.insert(
             "disabled".into(),
-            Alias::new("echo disabled".into(), false, false),
+            Alias::new("echo disabled".into(), false, AliasKind::Regular),
         );
 
         let default = format_list_with_width(
@@ -449,7 +604,7 @@ mod tests {
         assert_eq!(default[0]["name"], "test");
 
         let mut disabled = command(OutputFormat::Json);
-        disabled.disabled = true;
+        disabled.selection.disabled = true;
         let disabled = format_list_with_width(
             &catalog,
             &disabled,
@@ -464,7 +619,7 @@ mod tests {
         assert_eq!(disabled[0]["name"], "disabled");
 
         let mut all = command(OutputFormat::Json);
-        all.all = true;
+        all.selection.all = true;
         let all = format_list_with_width(
             &catalog,
             &all,
@@ -481,7 +636,7 @@ mod tests {
     #[test]
     fn status_policy_is_dynamic_unless_columns_are_explicit() {
         let mut all = command(OutputFormat::Human);
-        all.all = true;
+        all.selection.all = true;
         let output = format_list_with_width(
             &catalog(),
             &all,
@@ -529,7 +684,7 @@ mod tests {
     #[test]
     fn bash_hides_an_explicit_global_column() {
         let mut explicit = command(OutputFormat::Human);
-        explicit.columns = Some(vec![ListColumn::Name, ListColumn::Global]);
+        explicit.columns = Some(vec![ListColumn::Name, ListColumn::Kind]);
         let output = format_list_with_width(
             &catalog(),
             &explicit,
@@ -541,7 +696,7 @@ mod tests {
         .unwrap();
         assert_eq!(output, "Name\ntest\n");
 
-        explicit.columns = Some(vec![ListColumn::Global]);
+        explicit.columns = Some(vec![ListColumn::Kind]);
         let output = format_list_with_width(
             &catalog(),
             &explicit,
@@ -574,7 +729,7 @@ mod tests {
     #[test]
     fn tag_filters_require_every_tag() {
         let mut cmd = command(OutputFormat::Json);
-        cmd.tag = vec!["dev".into(), "missing".into()];
+        cmd.selection.tag = vec!["dev".into(), "missing".into()];
         let output = format_list_with_width(
             &catalog(),
             &cmd,
@@ -590,7 +745,7 @@ mod tests {
     #[test]
     fn invalid_patterns_and_empty_tables_are_handled() {
         let mut cmd = command(OutputFormat::Human);
-        cmd.pattern = Some("[".into());
+        cmd.selection.pattern = Some("[".into());
         assert_eq!(
             format_list_with_width(
                 &catalog(),
@@ -605,6 +760,8 @@ mod tests {
         assert_eq!(
             format_human(
                 &[],
+                &AliasCatalog::new(),
+                &ShellType::Bash,
                 &ListColumn::DEFAULTS,
                 &UserConfig::default(),
                 false,
@@ -617,17 +774,51 @@ mod tests {
     #[test]
     fn cells_are_single_line_styled_and_truncated_at_every_width() {
         let config = UserConfig::default();
-        let mut alias = Alias::new("line one\nline two\tend".into(), false, true);
+        let mut alias = Alias::new("line one\nline two\tend".into(), false, AliasKind::Global);
         alias.description = Some("first\rsecond".into());
 
-        assert_eq!(raw_cell(ListColumn::Status, "name", &alias, &config), "✘");
-        assert_eq!(raw_cell(ListColumn::Global, "name", &alias, &config), "⦾");
         assert_eq!(
-            raw_cell(ListColumn::Command, "name", &alias, &config),
+            raw_cell(
+                ListColumn::Status,
+                "name",
+                &alias,
+                &AliasCatalog::new(),
+                &ShellType::Bash,
+                &config
+            ),
+            "✘"
+        );
+        assert_eq!(
+            raw_cell(
+                ListColumn::Kind,
+                "name",
+                &alias,
+                &AliasCatalog::new(),
+                &ShellType::Bash,
+                &config
+            ),
+            "⦾"
+        );
+        assert_eq!(
+            raw_cell(
+                ListColumn::Command,
+                "name",
+                &alias,
+                &AliasCatalog::new(),
+                &ShellType::Bash,
+                &config
+            ),
             "line one line two end"
         );
         assert_eq!(
-            raw_cell(ListColumn::Description, "name", &alias, &config),
+            raw_cell(
+                ListColumn::Description,
+                "name",
+                &alias,
+                &AliasCatalog::new(),
+                &ShellType::Bash,
+                &config
+            ),
             "first second"
         );
         assert_eq!(truncate("abc", 3), "abc");
@@ -635,13 +826,26 @@ mod tests {
         assert_eq!(truncate("abc", 1), "…");
         assert_eq!(truncate("abcdef", 4), "abc…");
         assert_eq!(
-            styled_cell(ListColumn::Global, "⦾", &alias, &config, false),
+            styled_cell(ListColumn::Kind, "⦾", &alias, &config, false),
             "⦾"
         );
         assert_eq!(
             styled_cell(ListColumn::Status, "✘", &alias, &config, false),
             "✘"
         );
+
+        alias.kind = AliasKind::Suffix;
+        assert_eq!(
+            raw_cell(
+                ListColumn::Kind,
+                "name",
+                &alias,
+                &AliasCatalog::new(),
+                &ShellType::Zsh,
+                &config
+            ),
+            "⧉"
+        );
     }
 
     #[test]
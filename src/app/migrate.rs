@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use log::error;
+
+use super::CommandOutcome;
+use crate::catalog::io::{backup_catalog, restore_catalog_content};
+use crate::catalog::migrate::{CATALOG_VERSION, migrate_catalog};
+use crate::cli::interaction::{InteractionMode, prompt_migrate_catalog};
+use crate::cli::migrate::MigrateCommand;
+use crate::core::diff::line_diff;
+use crate::core::{Failure, Outcome};
+
+/// Convert the catalog to the current format, showing the change as a diff and
+/// keeping the original file as a backup.
+pub fn handle_migrate(
+    catalog_path: &Path,
+    loaded_content: &str,
+    loaded_stamp: &str,
+    args: MigrateCommand,
+    interaction_mode: InteractionMode,
+    quiet: bool,
+) -> Result<CommandOutcome, Failure> {
+    let migration = if catalog_path.exists() {
+        migrate_catalog(loaded_content).map_err(|migrate_error| {
+            error!("{migrate_error:#}");
+            Failure::InvalidCatalog
+        })?
+    } else {
+        None
+    };
+    let Some(migration) = migration else {
+        return Ok(CommandOutcome::with_message(
+            Outcome::NoChanges,
+            format!("Catalog already uses format version {CATALOG_VERSION}; nothing to migrate."),
+        ));
+    };
+
+    if !quiet {
+        println!(
+            "Migrating catalog '{}' from format version {} to {CATALOG_VERSION}:",
+            catalog_path.display(),
+            migration.from_version
+        );
+        for line in line_diff(loaded_content, &migration.content) {
+            println!("{line}");
+        }
+    }
+    if args.dry_run {
+        return Ok(CommandOutcome::with_message(
+            Outcome::NoChanges,
+            "Dry run: the catalog was not changed.",
+        ));
+    }
+    if !prompt_migrate_catalog(interaction_mode) {
+        return Ok(CommandOutcome::with_message(
+            Outcome::NoChanges,
+            "Migration declined. No changes made.",
+        ));
+    }
+
+    let backup = backup_catalog(catalog_path, migration.from_version).map_err(|backup_error| {
+        error!("{backup_error:#}");
+        Failure::CatalogWriteFailed
+    })?;
+    restore_catalog_content(catalog_path, &migration.content, loaded_stamp).map_err(
+        |write_error| {
+            error!("{write_error:#}");
+            Failure::CatalogWriteFailed
+        },
+    )?;
+    Ok(CommandOutcome::with_message(
+        Outcome::NoChanges,
+        format!(
+            "Migrated catalog to format version {CATALOG_VERSION}. The original was saved to '{}'.",
+            backup.display()
+        ),
+    ))
+}
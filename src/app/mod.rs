@@ -3,14 +3,21 @@ pub(crate) mod disable;
 pub(crate) mod doctor;
 pub(crate) mod edit;
 pub(crate) mod enable;
+pub(crate) mod env;
+pub(crate) mod export;
 pub(crate) mod file_path;
+pub(crate) mod history;
 pub(crate) mod import;
 pub(crate) mod init;
 pub(crate) mod list;
+pub(crate) mod migrate;
+pub(crate) mod profile;
 pub(crate) mod remove;
 pub(crate) mod rename;
 pub(crate) mod shell;
+pub(crate) mod status;
 pub(crate) mod sync;
+pub(crate) mod trust;
 
 use crate::core::Outcome;
 
@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use log::{error, warn};
+
+use super::CommandOutcome;
+use crate::catalog::profile::{load_active_profile, profile_state_path, save_active_profile};
+use crate::cli::profile::{ProfileAction, ProfileCommand};
+use crate::config::UserConfig;
+use crate::core::profile::TagExpression;
+use crate::core::{Failure, Outcome};
+
+pub const PROFILE_ENV_VAR: &str = "ALIASMGR_PROFILE";
+
+/// The profile this terminal selected through the environment.
+pub fn terminal_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV_VAR)
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+/// The terminal's profile, or else the one chosen with `profile use`.
+fn active_profile_name(catalog_path: &Path) -> Option<String> {
+    terminal_profile().or_else(
+        || match load_active_profile(&profile_state_path(catalog_path)) {
+            Ok(name) => name,
+            Err(load_error) => {
+                warn!("{load_error:#}");
+                None
+            }
+        },
+    )
+}
+
+/// The active profile and its tag expression. A profile that is no longer
+/// configured is ignored with a warning, leaving every enabled alias active.
+pub fn active_profile<'a>(
+    config: &'a UserConfig,
+    catalog_path: &Path,
+) -> Option<(String, &'a TagExpression)> {
+    let name = active_profile_name(catalog_path)?;
+    match config.profiles.get(&name) {
+        Some(expression) => Some((name, expression)),
+        None => {
+            warn!("Profile '{name}' is not configured; every enabled alias is active.");
+            None
+        }
+    }
+}
+
+fn save_profile(catalog_path: &Path, name: Option<&str>) -> Result<(), Failure> {
+    save_active_profile(&profile_state_path(catalog_path), name).map_err(|save_error| {
+        error!("{save_error:#}");
+        Failure::ProfileUnavailable
+    })
+}
+
+fn format_profiles(config: &UserConfig, active: Option<&str>) -> String {
+    if config.profiles.is_empty() {
+        return "No profiles are configured.".into();
+    }
+    let width = config.profiles.keys().map(String::len).max().unwrap_or(0);
+    config
+        .profiles
+        .iter()
+        .map(|(name, expression)| {
+            let marker = if active == Some(name.as_str()) {
+                '*'
+            } else {
+                ' '
+            };
+            format!("{marker} {name:width$}  {expression}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn handle_profile(
+    config: &UserConfig,
+    catalog_path: &Path,
+    cmd: ProfileCommand,
+) -> Result<CommandOutcome, Failure> {
+    let message = match cmd.action {
+        ProfileAction::List => {
+            let active = active_profile_name(catalog_path);
+            format_profiles(config, active.as_deref())
+        }
+        ProfileAction::Use { name } => {
+            if !config.profiles.contains_key(&name) {
+                return Err(Failure::ProfileDoesNotExist);
+            }
+            save_profile(catalog_path, Some(&name))?;
+            let mut message = format!("Using profile '{name}'.");
+            if let Some(terminal) = terminal_profile().filter(|terminal| *terminal != name) {
+                message.push_str(&format!(
+                    "\nThis terminal keeps profile '{terminal}' from {PROFILE_ENV_VAR}."
+                ));
+            }
+            message
+        }
+        ProfileAction::Clear => {
+            save_profile(catalog_path, None)?;
+            "Stopped using a profile; every enabled alias is active.".into()
+        }
+    };
+    Ok(CommandOutcome::with_message(Outcome::NoChanges, message))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use temp_env::with_var;
+
+    fn config() -> UserConfig {
+        let mut config = UserConfig::default();
+        for (name, expression) in [("work", "work | shell"), ("home", "!work")] {
+            config
+                .profiles
+                .insert(name.into(), TagExpression::parse(expression).unwrap());
+        }
+        config
+    }
+
+    fn run(catalog_path: &Path, action: ProfileAction) -> Result<String, Failure> {
+        handle_profile(&config(), catalog_path, ProfileCommand { action })
+            .map(|outcome| outcome.message.unwrap())
+    }
+
+    #[test]
+    fn profiles_are_used_listed_and_cleared() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog_path = directory.path().join("aliases.toml");
+        with_var(PROFILE_ENV_VAR, None::<&str>, || {
+            assert_eq!(
+                run(&catalog_path, ProfileAction::List).unwrap(),
+                "  home  !work\n  work  work | shell"
+            );
+            assert_eq!(
+                run(
+                    &catalog_path,
+                    ProfileAction::Use {
+                        name: "work".into()
+                    }
+                )
+                .unwrap(),
+                "Using profile 'work'."
+            );
+            assert_eq!(
+                run(&catalog_path, ProfileAction::List).unwrap(),
+                "  home  !work\n* work  work | shell"
+            );
+            let config = config();
+            let (name, expression) = active_profile(&config, &catalog_path).unwrap();
+            assert_eq!(
+                (name.as_str(), expression.to_string()),
+                ("work", "work | shell".into())
+            );
+
+            run(&catalog_path, ProfileAction::Clear).unwrap();
+            assert_eq!(active_profile(&config, &catalog_path), None);
+            assert_eq!(
+                run(
+                    &catalog_path,
+                    ProfileAction::Use {
+                        name: "travel".into()
+                    }
+                ),
+                Err(Failure::ProfileDoesNotExist)
+            );
+        });
+    }
+
+    #[test]
+    fn the_terminal_profile_overrides_the_persisted_one() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog_path = directory.path().join("aliases.toml");
+        with_var(PROFILE_ENV_VAR, Some("home"), || {
+            let message = run(
+                &catalog_path,
+                ProfileAction::Use {
+                    name: "work".into(),
+                },
+            )
+            .unwrap();
+            assert!(message.ends_with("This terminal keeps profile 'home' from ALIASMGR_PROFILE."));
+            let config = config();
+            assert_eq!(active_profile(&config, &catalog_path).unwrap().0, "home");
+        });
+        with_var(PROFILE_ENV_VAR, Some("travel"), || {
+            assert_eq!(active_profile(&config(), &catalog_path), None);
+        });
+    }
+}
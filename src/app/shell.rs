@@ -6,6 +6,11 @@ use std::fmt;
 pub enum ShellType {
     Bash,
     Zsh,
+    Fish,
+    #[value(alias = "nushell")]
+    Nu,
+    #[value(aliases = ["sh", "dash", "ksh"])]
+    Posix,
 }
 
 impl fmt::Display for ShellType {
@@ -13,6 +18,9 @@ impl fmt::Display for ShellType {
         match self {
             ShellType::Bash => write!(f, "BASH"),
             ShellType::Zsh => write!(f, "ZSH"),
+            ShellType::Fish => write!(f, "FISH"),
+            ShellType::Nu => write!(f, "NUSHELL"),
+            ShellType::Posix => write!(f, "POSIX"),
         }
     }
 }
@@ -25,6 +33,28 @@ pub fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
 
+/// Quote a value for fish, where single quotes only treat `\\` and `\'` as escapes.
+pub fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Quote a value as a Nushell double-quoted string literal.
+pub fn nu_quote(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 pub fn determine_shell() -> ShellType {
     match std::env::var(SHELL_ENV_VAR) {
         Ok(val) => match ShellType::from_str(&val, true) {
@@ -58,6 +88,9 @@ mod tests {
     fn test_shell_type_display() {
         assert_eq!(ShellType::Bash.to_string(), "BASH");
         assert_eq!(ShellType::Zsh.to_string(), "ZSH");
+        assert_eq!(ShellType::Fish.to_string(), "FISH");
+        assert_eq!(ShellType::Nu.to_string(), "NUSHELL");
+        assert_eq!(ShellType::Posix.to_string(), "POSIX");
     }
 
     #[test]
@@ -66,6 +99,19 @@ mod tests {
         assert_eq!(shell_quote("it's quoted"), "'it'\"'\"'s quoted'");
     }
 
+    #[test]
+    fn test_fish_quote() {
+        assert_eq!(fish_quote("plain value"), "'plain value'");
+        assert_eq!(fish_quote("it's quoted"), r"'it\'s quoted'");
+        assert_eq!(fish_quote(r"back\slash"), r"'back\\slash'");
+    }
+
+    #[test]
+    fn test_nu_quote() {
+        assert_eq!(nu_quote("plain value"), r#""plain value""#);
+        assert_eq!(nu_quote("say \"hi\"\\\nnext"), r#""say \"hi\"\\\nnext""#);
+    }
+
     #[test]
     fn test_determine_shell_default() {
         with_var(SHELL_ENV_VAR, None as Option<&str>, || {
@@ -100,5 +146,18 @@ mod tests {
             let shell = determine_shell();
             assert_eq!(shell, ShellType::Zsh);
         });
+        with_var(SHELL_ENV_VAR, Some("FISH"), || {
+            assert_eq!(determine_shell(), ShellType::Fish);
+        });
+        for value in ["nu", "NUSHELL"] {
+            with_var(SHELL_ENV_VAR, Some(value), || {
+                assert_eq!(determine_shell(), ShellType::Nu);
+            });
+        }
+        for value in ["POSIX", "sh", "dash", "ksh"] {
+            with_var(SHELL_ENV_VAR, Some(value), || {
+                assert_eq!(determine_shell(), ShellType::Posix);
+            });
+        }
     }
 }
@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use super::CommandOutcome;
+use super::shell::ShellType;
+use crate::catalog::types::AliasCatalog;
+use crate::cli::status::StatusCommand;
+use crate::core::sync::{CATALOG_REVISION_ENV_VAR, effective_revision};
+use crate::core::{Failure, Outcome};
+
+fn format_status(
+    shell: &ShellType,
+    catalog_path: &Path,
+    revision: &str,
+    terminal_revision: Option<&str>,
+) -> String {
+    let state = match terminal_revision {
+        None => "Terminal state is unavailable; run aliasmgr through the shell integration.",
+        Some(applied) if applied == revision => "Terminal is in sync with the catalog.",
+        Some(_) => "Terminal is out of sync; run 'aliasmgr sync' to reconcile it.",
+    };
+    let terminal_revision = match terminal_revision {
+        Some("") => "none",
+        Some(applied) => applied,
+        None => "unknown",
+    };
+    format!(
+        "Shell: {shell}\nCatalog: {}\nCatalog revision: {revision}\nTerminal revision: {terminal_revision}\n{state}",
+        catalog_path.display()
+    )
+}
+
+pub fn handle_status(
+    catalog: &AliasCatalog,
+    shell: &ShellType,
+    catalog_path: &Path,
+    cmd: StatusCommand,
+) -> Result<CommandOutcome, Failure> {
+    let revision = effective_revision(catalog, shell);
+    let terminal_revision = std::env::var(CATALOG_REVISION_ENV_VAR).ok();
+
+    let message = if cmd.revision {
+        revision
+    } else if cmd.terminal_revision {
+        terminal_revision.unwrap_or_default()
+    } else {
+        format_status(shell, catalog_path, &revision, terminal_revision.as_deref())
+    };
+    Ok(CommandOutcome::with_message(Outcome::NoChanges, message))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use temp_env::with_var;
+
+    fn command(revision: bool, terminal_revision: bool) -> StatusCommand {
+        StatusCommand {
+            revision,
+            terminal_revision,
+        }
+    }
+
+    #[test]
+    fn revision_query_prints_only_the_catalog_revision() {
+        let catalog = AliasCatalog::new();
+        let outcome = handle_status(
+            &catalog,
+            &ShellType::Bash,
+            Path::new("aliases.toml"),
+            command(true, false),
+        )
+        .unwrap();
+        assert_eq!(
+            outcome.message.unwrap(),
+            effective_revision(&catalog, &ShellType::Bash)
+        );
+    }
+
+    #[test]
+    fn status_compares_terminal_and_catalog_revisions() {
+        let catalog = AliasCatalog::new();
+        let revision = effective_revision(&catalog, &ShellType::Zsh);
+        let status = |cmd| {
+            handle_status(&catalog, &ShellType::Zsh, Path::new("aliases.toml"), cmd)
+                .unwrap()
+                .message
+                .unwrap()
+        };
+
+        with_var(CATALOG_REVISION_ENV_VAR, Some(&revision), || {
+            let message = status(command(false, false));
+            assert!(message.contains("Catalog: aliases.toml"));
+            assert!(message.ends_with("Terminal is in sync with the catalog."));
+            assert_eq!(status(command(false, true)), revision);
+        });
+        with_var(CATALOG_REVISION_ENV_VAR, Some(""), || {
+            let message = status(command(false, false));
+            assert!(message.contains("Terminal revision: none"));
+            assert!(message.contains("out of sync"));
+        });
+        with_var(CATALOG_REVISION_ENV_VAR, None::<&str>, || {
+            assert!(status(command(false, false)).contains("unavailable"));
+        });
+    }
+}
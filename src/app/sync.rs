@@ -1,39 +1,175 @@
+use super::file_path::CATALOG_FILE_ENV_VAR;
+use super::profile::terminal_profile;
 use super::shell::ShellType;
+use super::trust::current_project_file;
+use crate::catalog::io::catalog_path;
+use crate::catalog::layers::{is_layers_stamp_current, system_catalog_path};
+use crate::catalog::profile::profile_state_path;
+use crate::catalog::project::trust_store_path;
 use crate::catalog::types::AliasCatalog;
 use crate::cli::sync::ShellSyncCommand;
+use crate::config::configured_config_path;
 use crate::core::sync::{
-    CATALOG_REVISION_ENV_VAR, MANAGED_ALIASES_ENV_VAR, generate_reconciliation_script,
+    CATALOG_REVISION_ENV_VAR, CATALOG_STAMP_ENV_VAR, ENV_ORIGINALS_ENV_VAR,
+    MANAGED_ALIASES_ENV_VAR, TerminalState, executables_digest, generate_reconciliation_script,
 };
 use crate::core::{Failure, Outcome};
-use log::warn;
+use log::{error, warn};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn handle_sync() -> Result<Outcome, Failure> {
     warn!("Run 'aliasmgr sync' through the shell integration generated by 'aliasmgr init'.");
     Ok(Outcome::NoChanges)
 }
 
+const PROFILE_STAMP_PREFIX: &str = "profile\t";
+const REQUIRES_STAMP_PREFIX: &str = "requires\t";
+
+/// Check whether the catalog file still matches the stamp this terminal applied.
+///
+/// This runs before the configuration and catalog are loaded, so it only
+/// consults the environment and file metadata.
+pub fn is_catalog_unchanged() -> bool {
+    let applied_stamp = std::env::var(CATALOG_STAMP_ENV_VAR).unwrap_or_default();
+    if applied_stamp.is_empty() {
+        return false;
+    }
+    let Some(applied_stamp) = strip_requires_stamp(&applied_stamp) else {
+        return false;
+    };
+    let (profile, applied_stamp) = split_profile_stamp(applied_stamp);
+    if profile != terminal_profile().as_deref() {
+        return false;
+    }
+    let path = std::env::var_os(CATALOG_FILE_ENV_VAR).map(PathBuf::from);
+    let catalog_path = catalog_path(path.as_ref());
+    is_layers_stamp_current(
+        applied_stamp,
+        &catalog_path,
+        &watched_files(&catalog_path),
+        current_project_file().as_deref(),
+    )
+}
+
+/// Files outside the layer stack whose changes affect the merged catalog.
+pub fn watched_files(catalog_path: &Path) -> [PathBuf; 4] {
+    [
+        system_catalog_path(),
+        configured_config_path(),
+        trust_store_path(catalog_path),
+        profile_state_path(catalog_path),
+    ]
+}
+
+/// Prefix a layer stamp with the profile this terminal selected, so that
+/// changing `ALIASMGR_PROFILE` synchronizes the terminal again.
+pub fn profile_stamp(layers_stamp: String) -> String {
+    match terminal_profile() {
+        Some(profile) => format!("{PROFILE_STAMP_PREFIX}{profile}\n{layers_stamp}"),
+        None => layers_stamp,
+    }
+}
+
+fn split_profile_stamp(stamp: &str) -> (Option<&str>, &str) {
+    stamp
+        .strip_prefix(PROFILE_STAMP_PREFIX)
+        .and_then(|rest| rest.split_once('\n'))
+        .map_or((None, stamp), |(profile, rest)| (Some(profile), rest))
+}
+
+/// Prefix a stamp with the executables that `requires` conditions name and a
+/// digest of where they resolve on `PATH`, so that installing or removing one
+/// synchronizes the terminal again.
+///
+/// Names that do not fit on the stamp's line leave the stamp empty, which
+/// makes every prompt evaluate the catalog again.
+fn requires_stamp(catalog: &AliasCatalog, stamp: &str) -> String {
+    let names = catalog
+        .aliases
+        .values()
+        .flat_map(|alias| &alias.conditions.requires)
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>();
+    if names.is_empty() {
+        return stamp.to_owned();
+    }
+    if stamp.is_empty() || names.iter().any(|name| name.contains(['\t', '\n'])) {
+        return String::new();
+    }
+    let digest = executables_digest(names.iter().copied(), std::env::var_os("PATH").as_deref());
+    let names = names.into_iter().collect::<Vec<_>>().join("\t");
+    format!("{REQUIRES_STAMP_PREFIX}{digest}\t{names}\n{stamp}")
+}
+
+/// The rest of a stamp whose required executables still resolve as they did
+/// when it was applied, or `None` when one of them changed.
+fn strip_requires_stamp(stamp: &str) -> Option<&str> {
+    let Some(recorded) = stamp.strip_prefix(REQUIRES_STAMP_PREFIX) else {
+        return Some(stamp);
+    };
+    let (line, rest) = recorded.split_once('\n')?;
+    let (digest, names) = line.split_once('\t')?;
+    let current = executables_digest(names.split('\t'), std::env::var_os("PATH").as_deref());
+    (current == digest).then_some(rest)
+}
+
 pub fn handle_shell_sync(
     catalog: &AliasCatalog,
     shell: &ShellType,
+    catalog_stamp: &str,
     cmd: ShellSyncCommand,
 ) -> String {
     let managed_aliases = std::env::var(MANAGED_ALIASES_ENV_VAR).unwrap_or_default();
     let applied_revision = std::env::var(CATALOG_REVISION_ENV_VAR).unwrap_or_default();
+    let applied_stamp = std::env::var(CATALOG_STAMP_ENV_VAR).unwrap_or_default();
+    let env_originals = std::env::var(ENV_ORIGINALS_ENV_VAR).unwrap_or_default();
+    let applied = TerminalState {
+        managed_aliases: &managed_aliases,
+        revision: &applied_revision,
+        catalog_stamp: &applied_stamp,
+        env_originals: &env_originals,
+    };
+    let exported = catalog
+        .variables
+        .keys()
+        .filter_map(|name| Some((name.clone(), std::env::var(name).ok()?)))
+        .collect();
+    let catalog_stamp = requires_stamp(catalog, catalog_stamp);
 
     generate_reconciliation_script(
         catalog,
         shell,
-        &managed_aliases,
-        &applied_revision,
+        &applied,
+        &exported,
+        &catalog_stamp,
         cmd.if_changed && !cmd.force,
     )
 }
 
+pub fn emit_shell_sync(script: &str, output: Option<&Path>) -> Result<Outcome, Failure> {
+    let Some(path) = output else {
+        print!("{script}");
+        return Ok(Outcome::NoChanges);
+    };
+    fs::write(path, script).map_err(|write_error| {
+        error!("Could not write '{}': {write_error}", path.display());
+        Failure::SyncOutputFailed
+    })?;
+    Ok(Outcome::NoChanges)
+}
+
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
     use super::*;
-    use crate::catalog::types::Alias;
+    use crate::catalog::io::catalog_stamp;
+    use crate::catalog::layers::SYSTEM_CATALOG_ENV_VAR;
+    use crate::catalog::types::{Alias, AliasKind};
+    use crate::config::CONFIG_FILE_ENV_VAR;
+    use std::ffi::OsString;
+    use std::os::unix::fs::PermissionsExt;
     use temp_env::with_vars;
 
     #[test]
@@ -41,7 +177,7 @@ mod tests {
         let mut catalog = AliasCatalog::new();
         catalog.aliases.insert(
             "current".into(),
-            Alias::new("echo current".into(), true, false),
+            Alias::new("echo current".into(), true, AliasKind::Regular),
         );
 
         with_vars(
@@ -53,9 +189,11 @@ mod tests {
                 let script = handle_shell_sync(
                     &catalog,
                     &ShellType::Bash,
+                    "missing",
                     ShellSyncCommand {
                         force: false,
                         if_changed: true,
+                        output: None,
                     },
                 );
                 assert!(script.contains("unalias -- 'old'"));
@@ -63,4 +201,105 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn unchanged_stamp_skips_loading_the_catalog() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(&path, "ll = 'ls -la'\n").unwrap();
+        let stamp = catalog_stamp(&path);
+        let path = path.to_str().unwrap();
+        let missing = directory.path().join("missing.toml");
+        let missing = missing.to_str().unwrap();
+
+        with_vars(
+            [
+                (CATALOG_FILE_ENV_VAR, Some(path)),
+                (CONFIG_FILE_ENV_VAR, Some(missing)),
+                (SYSTEM_CATALOG_ENV_VAR, Some(missing)),
+                (CATALOG_STAMP_ENV_VAR, Some(stamp.as_str())),
+            ],
+            || assert!(is_catalog_unchanged()),
+        );
+        with_vars(
+            [
+                (CATALOG_FILE_ENV_VAR, Some(path)),
+                (CONFIG_FILE_ENV_VAR, Some(missing)),
+                (SYSTEM_CATALOG_ENV_VAR, Some(missing)),
+                (CATALOG_STAMP_ENV_VAR, Some("0:0:0")),
+            ],
+            || assert!(!is_catalog_unchanged()),
+        );
+        with_vars(
+            [
+                (CATALOG_FILE_ENV_VAR, Some(path)),
+                (CONFIG_FILE_ENV_VAR, Some(missing)),
+                (SYSTEM_CATALOG_ENV_VAR, Some(missing)),
+                (CATALOG_STAMP_ENV_VAR, None),
+            ],
+            || assert!(!is_catalog_unchanged()),
+        );
+    }
+
+    #[test]
+    fn required_executables_are_part_of_the_stamp() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(&path, "o = 'xdg-open'\n").unwrap();
+        let missing = directory.path().join("missing.toml");
+        let bin = directory.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert(
+            "ll".into(),
+            Alias::new("ls -la".into(), true, AliasKind::Regular),
+        );
+        assert_eq!(requires_stamp(&catalog, "1:10:7"), "1:10:7");
+        let mut open = Alias::new("xdg-open".into(), true, AliasKind::Regular);
+        open.conditions.requires.insert("xdg-open".into());
+        catalog.aliases.insert("o".into(), open);
+
+        let env = |stamp: Option<&str>| {
+            [
+                (CATALOG_FILE_ENV_VAR, Some(path.clone().into_os_string())),
+                (CONFIG_FILE_ENV_VAR, Some(missing.clone().into_os_string())),
+                (
+                    SYSTEM_CATALOG_ENV_VAR,
+                    Some(missing.clone().into_os_string()),
+                ),
+                ("PATH", Some(bin.clone().into_os_string())),
+                (CATALOG_STAMP_ENV_VAR, stamp.map(OsString::from)),
+            ]
+        };
+        let stamp = with_vars(env(None), || {
+            requires_stamp(&catalog, &catalog_stamp(&path))
+        });
+        assert!(stamp.starts_with(REQUIRES_STAMP_PREFIX));
+        with_vars(env(Some(&stamp)), || assert!(is_catalog_unchanged()));
+
+        let tool = bin.join("xdg-open");
+        fs::write(&tool, "").unwrap();
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+        with_vars(env(Some(&stamp)), || assert!(!is_catalog_unchanged()));
+
+        let mut odd = Alias::new("true".into(), true, AliasKind::Regular);
+        odd.conditions.requires.insert("a\tb".into());
+        catalog.aliases.insert("odd".into(), odd);
+        assert_eq!(requires_stamp(&catalog, "1:10:7"), "");
+    }
+
+    #[test]
+    fn shell_sync_output_replaces_the_module_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("sync.nu");
+        fs::write(&path, "stale").unwrap();
+
+        assert_eq!(emit_shell_sync("", Some(&path)), Ok(Outcome::NoChanges));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        assert_eq!(
+            emit_shell_sync("", Some(&directory.path().join("missing/sync.nu"))),
+            Err(Failure::SyncOutputFailed)
+        );
+    }
 }
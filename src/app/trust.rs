@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{error, warn};
+
+use super::CommandOutcome;
+use crate::catalog::io::parse_catalog;
+use crate::catalog::project::{
+    PROJECT_FILE_NAME, TrustStore, find_project_file, load_trust_store, save_trust_store,
+    trust_digest, trust_store_path,
+};
+use crate::catalog::types::AliasCatalog;
+use crate::cli::trust::{TrustCommand, UntrustCommand};
+use crate::core::{Failure, Outcome};
+
+/// The project file in effect for the shell's working directory.
+pub fn current_project_file() -> Option<PathBuf> {
+    std::env::current_dir()
+        .ok()
+        .and_then(|directory| find_project_file(&directory))
+}
+
+/// Resolve a directory or project file argument to the canonical project file.
+fn resolve_project_file(path: Option<&Path>) -> Result<PathBuf, Failure> {
+    let start = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir().map_err(|_| Failure::ProjectFileNotFound)?,
+    };
+    let file = if start.is_file() {
+        Some(start).filter(|file| file.file_name() == Some(PROJECT_FILE_NAME.as_ref()))
+    } else {
+        find_project_file(&start)
+    };
+    file.and_then(|file| fs::canonicalize(file).ok())
+        .ok_or(Failure::ProjectFileNotFound)
+}
+
+fn load_store(path: &Path) -> Result<TrustStore, Failure> {
+    load_trust_store(path).map_err(|load_error| {
+        error!("{load_error:#}");
+        Failure::TrustStoreUnavailable
+    })
+}
+
+fn save_store(path: &Path, store: &TrustStore) -> Result<(), Failure> {
+    save_trust_store(path, store).map_err(|save_error| {
+        error!("{save_error:#}");
+        Failure::TrustStoreUnavailable
+    })
+}
+
+pub fn handle_trust(catalog_path: &Path, cmd: TrustCommand) -> Result<CommandOutcome, Failure> {
+    let file = resolve_project_file(cmd.path.as_deref())?;
+    let content = fs::read_to_string(&file).map_err(|read_error| {
+        error!("Could not read '{}': {read_error}", file.display());
+        Failure::ProjectFileNotFound
+    })?;
+    parse_catalog(&content).map_err(|parse_error| {
+        error!("{}: {parse_error:#}", file.display());
+        Failure::InvalidCatalog
+    })?;
+
+    let store_path = trust_store_path(catalog_path);
+    let mut store = load_store(&store_path)?;
+    store.projects.insert(file.clone(), trust_digest(&content));
+    save_store(&store_path, &store)?;
+    Ok(CommandOutcome::with_message(
+        Outcome::NoChanges,
+        format!("Trusted '{}'.", file.display()),
+    ))
+}
+
+pub fn handle_untrust(catalog_path: &Path, cmd: UntrustCommand) -> Result<CommandOutcome, Failure> {
+    let file = resolve_project_file(cmd.path.as_deref())?;
+    let store_path = trust_store_path(catalog_path);
+    let mut store = load_store(&store_path)?;
+    let message = if store.projects.remove(&file).is_some() {
+        save_store(&store_path, &store)?;
+        format!("No longer trusting '{}'.", file.display())
+    } else {
+        format!("'{}' was not trusted.", file.display())
+    };
+    Ok(CommandOutcome::with_message(Outcome::NoChanges, message))
+}
+
+/// Load a project file's aliases if the user trusted its current content.
+/// Untrusted, changed, and unreadable project files are skipped with a warning.
+pub fn trusted_project_catalog(catalog_path: &Path, project_file: &Path) -> Option<AliasCatalog> {
+    let file = fs::canonicalize(project_file).ok()?;
+    let content = match fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(read_error) => {
+            warn!(
+                "Skipping project aliases in '{}': {read_error}",
+                file.display()
+            );
+            return None;
+        }
+    };
+    let store = match load_trust_store(&trust_store_path(catalog_path)) {
+        Ok(store) => store,
+        Err(load_error) => {
+            warn!("Skipping project aliases: {load_error:#}");
+            return None;
+        }
+    };
+    if !store.is_trusted(&file, &trust_digest(&content)) {
+        warn!(
+            "Project aliases in '{}' are new or changed; run 'aliasmgr trust' to load them.",
+            file.display()
+        );
+        return None;
+    }
+    match parse_catalog(&content) {
+        Ok(catalog) => Some(catalog),
+        Err(parse_error) => {
+            warn!(
+                "Skipping project aliases in '{}': {parse_error:#}",
+                file.display()
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_aliases_load_only_while_trusted_and_unchanged() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog_path = directory.path().join("aliases.toml");
+        let project = directory.path().join("repo");
+        fs::create_dir_all(project.join("src")).unwrap();
+        let project_file = project.join(PROJECT_FILE_NAME);
+        fs::write(&project_file, "b = 'cargo build'\n").unwrap();
+
+        assert_eq!(trusted_project_catalog(&catalog_path, &project_file), None);
+
+        let outcome = handle_trust(
+            &catalog_path,
+            TrustCommand {
+                path: Some(project.join("src")),
+            },
+        )
+        .unwrap();
+        assert_eq!(outcome.outcome, Outcome::NoChanges);
+        let catalog = trusted_project_catalog(&catalog_path, &project_file).unwrap();
+        assert_eq!(catalog.aliases["b"].command, "cargo build");
+
+        fs::write(&project_file, "b = 'rm -rf /'\n").unwrap();
+        assert_eq!(trusted_project_catalog(&catalog_path, &project_file), None);
+
+        handle_trust(
+            &catalog_path,
+            TrustCommand {
+                path: Some(project_file.clone()),
+            },
+        )
+        .unwrap();
+        assert!(trusted_project_catalog(&catalog_path, &project_file).is_some());
+
+        let outcome = handle_untrust(
+            &catalog_path,
+            UntrustCommand {
+                path: Some(project.clone()),
+            },
+        )
+        .unwrap();
+        assert!(outcome.message.unwrap().starts_with("No longer trusting"));
+        assert_eq!(trusted_project_catalog(&catalog_path, &project_file), None);
+    }
+
+    #[test]
+    fn trust_requires_a_valid_project_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let catalog_path = directory.path().join("aliases.toml");
+        let other = directory.path().join("aliases.sh");
+        fs::write(&other, "").unwrap();
+        for path in [directory.path().to_path_buf(), other] {
+            assert_eq!(
+                handle_trust(&catalog_path, TrustCommand { path: Some(path) }),
+                Err(Failure::ProjectFileNotFound)
+            );
+        }
+
+        fs::write(directory.path().join(PROJECT_FILE_NAME), "ll = [").unwrap();
+        assert_eq!(
+            handle_trust(
+                &catalog_path,
+                TrustCommand {
+                    path: Some(directory.path().into()),
+                },
+            ),
+            Err(Failure::InvalidCatalog)
+        );
+        assert!(!trust_store_path(&catalog_path).exists());
+    }
+}
@@ -1,14 +1,24 @@
 //! Load and save the alias catalog.
 
-use std::fs;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use anyhow::{Context, Result, bail};
 use log::{debug, info, warn};
-use toml_edit::{DocumentMut, InlineTable, Item};
+use toml_edit::{DocumentMut, InlineTable, Item, Key, Table, Value};
 
-use super::spec::{AliasCatalogSpec, convert_spec_to_catalog};
-use super::types::{Alias, AliasCatalog};
+use super::migrate::{CATALOG_VERSION, VERSION_KEY, catalog_version, set_catalog_version};
+use super::spec::{
+    AliasCatalogSpec, ENV_TABLE, EnvSpec, convert_spec_to_catalog, convert_variable_spec,
+};
+use super::types::{Alias, AliasCatalog, AliasKind, Variable};
+
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
 
 pub fn catalog_path(path: Option<&PathBuf>) -> PathBuf {
     if let Some(path) = path {
@@ -22,58 +32,418 @@ pub fn catalog_path(path: Option<&PathBuf>) -> PathBuf {
         .join("aliases.toml")
 }
 
-pub fn load_catalog(path: &PathBuf) -> Result<AliasCatalog> {
+/// Exclusive advisory lock on a catalog, released when dropped.
+pub struct CatalogLock {
+    _file: File,
+}
+
+/// Follow symbolic links so that a linked catalog, such as one in a dotfiles
+/// repository, is locked and replaced at its real location.
+fn resolve_symlinks(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn sibling_path(path: &Path, prefix: &str, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(prefix);
+    name.push(path.file_name().unwrap_or_default());
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Lock the catalog for a load, mutate, and save cycle.
+///
+/// The lock is taken on a `.lock` file next to the catalog because the
+/// catalog itself is replaced on every save.
+pub fn lock_catalog(path: &Path) -> Result<CatalogLock> {
+    let lock_path = sibling_path(&resolve_symlinks(path), "", ".lock");
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("could not open lock file '{}'", lock_path.display()))?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            warn!("Waiting for another aliasmgr command to finish updating the catalog.");
+            file.lock()
+                .with_context(|| format!("could not lock '{}'", lock_path.display()))?;
+        }
+        Err(TryLockError::Error(error)) => {
+            return Err(error).with_context(|| format!("could not lock '{}'", lock_path.display()));
+        }
+    }
+    Ok(CatalogLock { _file: file })
+}
+
+/// Describe the catalog file's modification time, size, and inode.
+///
+/// Any save or replacement changes the stamp, so an unchanged stamp lets the
+/// prompt hook skip parsing the catalog.
+pub fn catalog_stamp(path: &Path) -> String {
+    let Ok(metadata) = fs::metadata(path) else {
+        return "missing".into();
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos());
+    #[cfg(unix)]
+    let inode = metadata.ino();
+    #[cfg(not(unix))]
+    let inode = 0;
+    format!("{modified}:{}:{inode}", metadata.len())
+}
+
+/// Read the raw catalog text; a missing catalog is empty.
+pub fn read_catalog_content(path: &Path) -> Result<String> {
     info!("Loading catalog from {:?}", path);
     if !path.exists() {
-        return Ok(AliasCatalog::new());
+        return Ok(String::new());
     }
+    Ok(fs::read_to_string(path)?)
+}
 
-    let content = fs::read_to_string(path)?;
-    let document = content.parse::<DocumentMut>()?;
+#[cfg(test)]
+pub fn load_catalog(path: &Path) -> Result<AliasCatalog> {
+    parse_catalog(&read_catalog_content(path)?)
+}
+
+pub fn parse_catalog(content: &str) -> Result<AliasCatalog> {
+    let mut document = content.parse::<DocumentMut>()?;
+    let version = catalog_version(&document);
+    if version > CATALOG_VERSION {
+        bail!(
+            "catalog format version {version} requires a newer aliasmgr; this version supports version {CATALOG_VERSION}"
+        );
+    }
+    let env = match document.get(ENV_TABLE) {
+        Some(item) if item.is_table() => document.remove(ENV_TABLE),
+        _ => None,
+    };
     if let Some((name, _)) = document.iter().find(|(_, item)| item.is_table()) {
         bail!(
-            "legacy alias groups are unsupported (found group '{name}'); migrate the catalog before using this version"
+            "legacy alias groups are unsupported (found group '{name}'); run 'aliasmgr migrate' to convert the catalog"
         );
     }
+    if version < CATALOG_VERSION {
+        bail!(
+            "catalog format version {version} is unsupported; run 'aliasmgr migrate' to convert the catalog"
+        );
+    }
+    if document.get(VERSION_KEY).is_some_and(Item::is_integer) {
+        document.remove(VERSION_KEY);
+    }
 
-    let spec: AliasCatalogSpec = toml::from_str(&content)?;
-    Ok(convert_spec_to_catalog(spec))
+    let spec: AliasCatalogSpec = toml::from_str(&document.to_string())?;
+    let mut catalog = convert_spec_to_catalog(spec).map_err(anyhow::Error::msg)?;
+    if let Some(Item::Table(env)) = env {
+        let spec: EnvSpec = toml::from_str(&DocumentMut::from(env).to_string())
+            .with_context(|| format!("invalid [{ENV_TABLE}] table"))?;
+        catalog.variables = spec
+            .variables
+            .into_iter()
+            .map(|(name, spec)| (name, convert_variable_spec(spec)))
+            .collect();
+    }
+    Ok(catalog)
 }
 
-fn build_alias_item(alias: &mut Alias) -> Item {
+pub(super) fn build_alias_value(alias: &mut Alias) -> Value {
     alias.refresh_representation();
     if !alias.detailed {
-        return Item::Value(alias.command.clone().into());
+        return alias.command.clone().into();
     }
 
     let mut inline = InlineTable::new();
-    inline.insert("command", alias.command.clone().into());
+    if alias.shell_commands.is_empty() {
+        inline.insert("command", alias.command.clone().into());
+    } else {
+        let mut commands = InlineTable::new();
+        commands.insert("default", alias.command.clone().into());
+        for (shell, command) in &alias.shell_commands {
+            commands.insert(shell, command.clone().into());
+        }
+        commands.set_dotted(true);
+        inline.insert("command", commands.into());
+    }
     inline.insert("enabled", alias.enabled.into());
-    inline.insert("global", alias.global.into());
+    // Regular and global aliases keep the `global` flag that older catalogs
+    // use, so saving does not rewrite them.
+    match alias.kind {
+        AliasKind::Regular => inline.insert("global", false.into()),
+        AliasKind::Global => inline.insert("global", true.into()),
+        AliasKind::Suffix => inline.insert("kind", alias.kind.as_str().into()),
+    };
+    if alias.function {
+        inline.insert("function", true.into());
+    }
     if let Some(description) = &alias.description {
         inline.insert("description", description.clone().into());
     }
-    if !alias.tags.is_empty() {
-        let mut tags = toml_edit::Array::new();
-        for tag in &alias.tags {
-            tags.push(tag.as_str());
+    let conditions = &alias.conditions;
+    for (key, values) in [
+        ("tags", &alias.tags),
+        ("hosts", &conditions.hosts),
+        ("os", &conditions.os),
+        ("requires", &conditions.requires),
+    ] {
+        if !values.is_empty() {
+            let mut array = toml_edit::Array::new();
+            for value in values {
+                array.push(value.as_str());
+            }
+            inline.insert(key, Value::Array(array));
         }
-        inline.insert("tags", toml_edit::Value::Array(tags));
     }
     inline.set_dotted(false);
-    Item::Value(inline.into())
+    inline.into()
 }
 
-fn build_toml_document(catalog: &mut AliasCatalog) -> DocumentMut {
-    let mut document = DocumentMut::new();
+pub(super) fn build_variable_value(variable: &mut Variable) -> Value {
+    variable.refresh_representation();
+    if !variable.detailed {
+        return variable.value.clone().into();
+    }
+
+    let mut inline = InlineTable::new();
+    inline.insert("value", variable.value.clone().into());
+    inline.insert("enabled", variable.enabled.into());
+    if let Some(description) = &variable.description {
+        inline.insert("description", description.clone().into());
+    }
+    if !variable.tags.is_empty() {
+        let mut array = toml_edit::Array::new();
+        for tag in &variable.tags {
+            array.push(tag.as_str());
+        }
+        inline.insert("tags", Value::Array(array));
+    }
+    inline.into()
+}
+
+/// Compare values by content, ignoring quoting style, whitespace, and key order.
+fn is_same_value(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => left.value() == right.value(),
+        (Value::Boolean(left), Value::Boolean(right)) => left.value() == right.value(),
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| is_same_value(left, right))
+        }
+        (Value::InlineTable(left), Value::InlineTable(right)) => {
+            left.len() == right.len()
+                && left.iter().all(|(key, value)| {
+                    right
+                        .get(key)
+                        .is_some_and(|other| is_same_value(value, other))
+                })
+        }
+        _ => false,
+    }
+}
+
+fn key_prefix(document: &DocumentMut, name: &str) -> String {
+    document
+        .key(name)
+        .and_then(|key| key.leaf_decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Remove an alias while keeping comments that are separated from it by a
+/// blank line, such as section headers, in front of the following alias.
+fn remove_alias_entry(document: &mut DocumentMut, name: &str) {
+    let prefix = key_prefix(document, name);
+    let detached = prefix.rfind("\n\n").map_or("", |end| &prefix[..end + 2]);
+    let next = document
+        .iter()
+        .map(|(key, _)| key)
+        .skip_while(|key| *key != name)
+        .nth(1)
+        .map(str::to_owned);
+    document.remove(name);
+
+    if detached.trim().is_empty() {
+        return;
+    }
+    if let Some(next) = next {
+        let next_prefix = key_prefix(document, &next);
+        if let Some(mut key) = document.key_mut(&next) {
+            key.leaf_decor_mut()
+                .set_prefix(format!("{detached}{next_prefix}"));
+        }
+    } else {
+        let trailing = document.trailing().as_str().unwrap_or_default().to_owned();
+        document.set_trailing(format!("{detached}{trailing}"));
+    }
+}
+
+/// Apply the catalog to a previously saved document, leaving unchanged aliases
+/// and surrounding comments and blank lines untouched.
+fn update_toml_document(document: &mut DocumentMut, catalog: &mut AliasCatalog) {
+    let stale = document
+        .iter()
+        .filter(|(name, item)| *name != VERSION_KEY || !item.is_integer())
+        .filter(|(name, item)| *name != ENV_TABLE || !item.is_table())
+        .map(|(name, _)| name.to_owned())
+        .filter(|name| !catalog.aliases.contains_key(name))
+        .collect::<Vec<_>>();
+    for name in stale {
+        remove_alias_entry(document, &name);
+    }
+
     for (name, alias) in &mut catalog.aliases {
-        document[name] = build_alias_item(alias);
+        let mut value = build_alias_value(alias);
+        match document.get_mut(name).and_then(Item::as_value_mut) {
+            Some(existing) if is_same_value(existing, &value) => {}
+            Some(existing) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            None => {
+                // Keep the version key of a new catalog apart from the aliases.
+                let mut key = Key::new(name.as_str());
+                if document.len() == 1 && document.get(VERSION_KEY).is_some_and(Item::is_integer) {
+                    key.leaf_decor_mut().set_prefix("\n");
+                }
+                document.insert_formatted(&key, Item::Value(value));
+            }
+        }
+    }
+}
+
+/// Apply the catalog's variables to the `[env]` table, which is removed once
+/// it has no variables left.
+fn update_env_table(document: &mut DocumentMut, variables: &mut BTreeMap<String, Variable>) {
+    let has_table = document.get(ENV_TABLE).is_some_and(Item::is_table);
+    if variables.is_empty() {
+        if has_table {
+            document.remove(ENV_TABLE);
+        }
+        return;
     }
+    if !has_table {
+        let mut table = Table::new();
+        table.decor_mut().set_prefix("\n");
+        document.insert(ENV_TABLE, Item::Table(table));
+    }
+    let Some(table) = document.get_mut(ENV_TABLE).and_then(Item::as_table_mut) else {
+        return;
+    };
+    let stale = table
+        .iter()
+        .map(|(name, _)| name.to_owned())
+        .filter(|name| !variables.contains_key(name))
+        .collect::<Vec<_>>();
+    for name in stale {
+        table.remove(&name);
+    }
+    for (name, variable) in variables {
+        let mut value = build_variable_value(variable);
+        match table.get_mut(name).and_then(Item::as_value_mut) {
+            Some(existing) if is_same_value(existing, &value) => {}
+            Some(existing) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            None => {
+                table.insert(name, Item::Value(value));
+            }
+        }
+    }
+}
+
+fn new_document() -> DocumentMut {
+    let mut document = DocumentMut::new();
+    set_catalog_version(&mut document);
     document
 }
 
-pub fn save_catalog(catalog: &mut AliasCatalog, path: &PathBuf) -> Result<()> {
-    let content = build_toml_document(catalog).to_string();
+/// Render aliases as the text of a new catalog file.
+pub fn render_catalog(catalog: &mut AliasCatalog) -> String {
+    let mut document = new_document();
+    update_toml_document(&mut document, catalog);
+    update_env_table(&mut document, &mut catalog.variables);
+    document.to_string()
+}
+
+/// Load the document to patch; new catalogs start with the version key.
+fn read_saved_document(path: &Path) -> DocumentMut {
+    let Ok(content) = fs::read_to_string(path) else {
+        return new_document();
+    };
+    content.parse::<DocumentMut>().unwrap_or_else(|error| {
+        warn!("could not preserve the layout of {:?}: {error}", path);
+        DocumentMut::new()
+    })
+}
+
+/// Replace the file through a temporary sibling so readers never observe a
+/// partially written catalog.
+pub(super) fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    let target = resolve_symlinks(path);
+    let temporary = sibling_path(&target, ".", &format!(".{}.tmp", std::process::id()));
+    let result = (|| -> std::io::Result<()> {
+        let mut file = File::create(&temporary)?;
+        if let Ok(metadata) = fs::metadata(&target) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary, &target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+fn ensure_unmodified(path: &Path, loaded_stamp: &str) -> Result<()> {
+    if catalog_stamp(path) != loaded_stamp {
+        bail!(
+            "catalog '{}' was modified by another program since it was loaded; no changes were saved",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Save the catalog and return the written text, failing instead of
+/// overwriting changes made to the file since it was loaded with the given
+/// [`catalog_stamp`].
+pub fn save_catalog(
+    catalog: &mut AliasCatalog,
+    path: &PathBuf,
+    sort: bool,
+    loaded_stamp: &str,
+) -> Result<String> {
+    ensure_unmodified(path, loaded_stamp)?;
+    if !catalog.variables.is_empty() && catalog.aliases.contains_key(ENV_TABLE) {
+        bail!(
+            "an alias named '{ENV_TABLE}' cannot be saved together with the [{ENV_TABLE}] table; rename the alias first"
+        );
+    }
+    let mut document = read_saved_document(path);
+    update_toml_document(&mut document, catalog);
+    update_env_table(&mut document, &mut catalog.variables);
+    if sort {
+        document.sort_values_by(|left, _, right, _| {
+            (left.get() != VERSION_KEY, left.get()).cmp(&(right.get() != VERSION_KEY, right.get()))
+        });
+        if let Some(env) = document.get_mut(ENV_TABLE).and_then(Item::as_table_mut) {
+            env.sort_values();
+        }
+    }
+    let content = document.to_string();
     if !path.exists() {
         warn!("alias catalog file {:?} does not exist, creating it", path);
     }
@@ -81,7 +451,29 @@ pub fn save_catalog(catalog: &mut AliasCatalog, path: &PathBuf) -> Result<()> {
         fs::create_dir_all(parent)?;
     }
     debug!("Saving catalog to {:?}", path);
-    fs::write(path, content).with_context(|| format!("could not save catalog '{}'", path.display()))
+    write_atomically(path, &content)
+        .with_context(|| format!("could not save catalog '{}'", path.display()))?;
+    Ok(content)
+}
+
+/// Copy the catalog next to itself before a format migration, e.g. to
+/// `aliases.toml.v1.bak`, and return the copy's path.
+pub fn backup_catalog(path: &Path, version: i64) -> Result<PathBuf> {
+    let source = resolve_symlinks(path);
+    let backup = sibling_path(&source, "", &format!(".v{version}.bak"));
+    fs::copy(&source, &backup)
+        .with_context(|| format!("could not back up catalog to '{}'", backup.display()))?;
+    Ok(backup)
+}
+
+/// Replace the catalog with previously saved text, such as a journal entry.
+pub fn restore_catalog_content(path: &Path, content: &str, loaded_stamp: &str) -> Result<()> {
+    ensure_unmodified(path, loaded_stamp)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomically(path, content)
+        .with_context(|| format!("could not restore catalog '{}'", path.display()))
 }
 
 #[cfg(test)]
@@ -90,54 +482,206 @@ mod tests {
     use super::*;
     use assert_fs::TempDir;
 
+    #[test]
+    fn concurrent_modification_is_not_overwritten() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(&path, "ll = \"ls\"\n").unwrap();
+        let loaded_stamp = catalog_stamp(&path);
+        let mut catalog = load_catalog(&path).unwrap();
+        fs::write(&path, "ll = \"ls\"\nother = \"edited elsewhere\"\n").unwrap();
+
+        catalog.aliases.remove("ll");
+        let error = save_catalog(&mut catalog, &path, false, &loaded_stamp).unwrap_err();
+        assert!(error.to_string().contains("modified by another program"));
+        assert!(fs::read_to_string(&path).unwrap().contains("other"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saves_replace_symlink_targets_and_keep_permissions() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let directory = TempDir::new().unwrap();
+        let target = directory.path().join("dotfiles.toml");
+        let link = directory.path().join("aliases.toml");
+        fs::write(&target, "ll = \"ls\"\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        symlink(&target, &link).unwrap();
+        let mut catalog = load_catalog(&link).unwrap();
+        catalog.aliases.get_mut("ll").unwrap().command = "ls -la".into();
+
+        save_catalog(&mut catalog, &link, false, &catalog_stamp(&link)).unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "ll = \"ls -la\"\n");
+        assert_eq!(
+            fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn catalog_lock_is_exclusive_until_dropped() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        let lock = lock_catalog(&path).unwrap();
+        let lock_file = File::open(directory.path().join("aliases.toml.lock")).unwrap();
+        assert!(matches!(
+            lock_file.try_lock(),
+            Err(TryLockError::WouldBlock)
+        ));
+
+        drop(lock);
+        assert!(lock_file.try_lock().is_ok());
+    }
+
+    #[test]
+    fn stamp_changes_when_the_catalog_is_rewritten() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        assert_eq!(catalog_stamp(&path), "missing");
+
+        fs::write(&path, "ll = 'ls'\n").unwrap();
+        let first = catalog_stamp(&path);
+        assert_eq!(catalog_stamp(&path), first);
+
+        fs::write(&path, "ll = 'ls -la'\n").unwrap();
+        assert_ne!(catalog_stamp(&path), first);
+    }
+
     #[test]
     fn metadata_round_trip_is_sorted_and_detailed() {
         let directory = TempDir::new().unwrap();
         let path = directory.path().join("aliases.toml");
         let mut catalog = AliasCatalog::new();
-        let mut alias = Alias::new("cargo test".into(), true, false);
+        let mut alias = Alias::new("cargo test".into(), true, AliasKind::Regular);
         alias.description = Some("Run tests".into());
         alias
             .tags
             .extend(["rust".into(), "dev".into(), "rust".into()]);
         catalog.aliases.insert("test".into(), alias);
 
-        save_catalog(&mut catalog, &path).unwrap();
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
         let saved = fs::read_to_string(&path).unwrap();
         assert!(saved.contains("tags = [\"dev\", \"rust\"]"));
         assert_eq!(load_catalog(&path).unwrap(), catalog);
     }
 
+    #[test]
+    fn shell_commands_round_trip_as_dotted_keys() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        let mut catalog = AliasCatalog::new();
+        let mut alias = Alias::new("exec $SHELL".into(), true, AliasKind::Regular);
+        alias
+            .shell_commands
+            .insert("fish".into(), "exec fish".into());
+        catalog.aliases.insert("reload".into(), alias);
+
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.contains(
+            "reload = { command.default = \"exec $SHELL\", command.fish = \"exec fish\", enabled = true"
+        ));
+        assert_eq!(load_catalog(&path).unwrap(), catalog);
+    }
+
+    #[test]
+    fn variables_round_trip_in_an_env_table_after_the_aliases() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(
+            &path,
+            "ll = \"ls -la\"\n\n[env]\n# Preferred editor\nEDITOR = \"vi\"\nPAGER = \"less\"\n",
+        )
+        .unwrap();
+        let mut catalog = load_catalog(&path).unwrap();
+        assert_eq!(catalog.variables["EDITOR"].value, "vi");
+        assert!(!catalog.aliases.contains_key(ENV_TABLE));
+
+        catalog.variables.get_mut("EDITOR").unwrap().value = "nvim".into();
+        catalog.variables.remove("PAGER");
+        catalog
+            .variables
+            .insert("VISUAL".into(), Variable::new("code".into(), false));
+        catalog.aliases.insert(
+            "gs".into(),
+            Alias::new("git status".into(), true, AliasKind::Regular),
+        );
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                "ll = \"ls -la\"\n",
+                "gs = \"git status\"\n",
+                "\n",
+                "[env]\n",
+                "# Preferred editor\n",
+                "EDITOR = \"nvim\"\n",
+                "VISUAL = { value = \"code\", enabled = false }\n",
+            )
+        );
+        assert_eq!(load_catalog(&path).unwrap(), catalog);
+
+        catalog.variables.clear();
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "ll = \"ls -la\"\ngs = \"git status\"\n"
+        );
+    }
+
+    #[test]
+    fn an_env_alias_cannot_be_saved_with_variables() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert(
+            ENV_TABLE.into(),
+            Alias::new("env | sort".into(), true, AliasKind::Regular),
+        );
+        catalog
+            .variables
+            .insert("EDITOR".into(), Variable::new("nvim".into(), true));
+
+        let error = save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap_err();
+        assert!(error.to_string().contains("rename the alias first"));
+    }
+
     #[test]
     fn saving_refreshes_each_alias_representation() {
         let directory = TempDir::new().unwrap();
         let path = directory.path().join("aliases.toml");
         let mut catalog = AliasCatalog::new();
 
-        let mut disabled = Alias::new("disabled".into(), true, false);
+        let mut disabled = Alias::new("disabled".into(), true, AliasKind::Regular);
         disabled.enabled = false;
         catalog.aliases.insert("disabled".into(), disabled);
 
-        let mut global = Alias::new("global".into(), true, false);
-        global.global = true;
+        let mut global = Alias::new("global".into(), true, AliasKind::Regular);
+        global.kind = AliasKind::Global;
         catalog.aliases.insert("global".into(), global);
 
-        let mut described = Alias::new("described".into(), true, false);
+        let mut described = Alias::new("described".into(), true, AliasKind::Regular);
         described.description = Some("Description".into());
         catalog.aliases.insert("described".into(), described);
 
-        let mut tagged = Alias::new("tagged".into(), true, false);
+        let mut tagged = Alias::new("tagged".into(), true, AliasKind::Regular);
         tagged.tags.insert("tag".into());
         catalog.aliases.insert("tagged".into(), tagged);
 
         assert!(catalog.aliases.values().all(|alias| !alias.detailed));
-        save_catalog(&mut catalog, &path).unwrap();
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
 
         assert!(catalog.aliases.values().all(|alias| alias.detailed));
         let saved = fs::read_to_string(&path).unwrap();
         assert_eq!(
             saved,
             concat!(
+                "version = 2\n",
+                "\n",
                 "described = { command = \"described\", enabled = true, global = false, description = \"Description\" }\n",
                 "disabled = { command = \"disabled\", enabled = false, global = false }\n",
                 "global = { command = \"global\", enabled = true, global = true }\n",
@@ -159,13 +703,130 @@ mod tests {
         let mut catalog = load_catalog(&path).unwrap();
         assert!(catalog.aliases["ll"].detailed);
 
-        save_catalog(&mut catalog, &path).unwrap();
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
 
         assert!(!catalog.aliases["ll"].detailed);
         assert_eq!(fs::read_to_string(&path).unwrap(), "ll = \"ls -la\"\n");
         assert_eq!(load_catalog(&path).unwrap(), catalog);
     }
 
+    #[test]
+    fn saving_preserves_comments_blank_lines_and_order() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(
+            &path,
+            concat!(
+                "# My aliases\n",
+                "\n",
+                "# --- git ---\n",
+                "\n",
+                "# Short status\n",
+                "gs = 'git status' # short status\n",
+                "gd = \"git diff\"\n",
+                "\n",
+                "# --- files ---\n",
+                "\n",
+                "# Long listing\n",
+                "ll = \"ls -l\"  # long\n",
+                "la = \"ls -a\"\n",
+                "# end of file\n",
+            ),
+        )
+        .unwrap();
+        let mut catalog = load_catalog(&path).unwrap();
+        catalog.aliases.remove("gs");
+        catalog.aliases.get_mut("ll").unwrap().command = "ls -la".into();
+        catalog.aliases.insert(
+            "b".into(),
+            Alias::new("cargo build".into(), true, AliasKind::Regular),
+        );
+
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                "# My aliases\n",
+                "\n",
+                "# --- git ---\n",
+                "\n",
+                "gd = \"git diff\"\n",
+                "\n",
+                "# --- files ---\n",
+                "\n",
+                "# Long listing\n",
+                "ll = \"ls -la\"  # long\n",
+                "la = \"ls -a\"\n",
+                "b = \"cargo build\"\n",
+                "# end of file\n",
+            )
+        );
+        assert_eq!(load_catalog(&path).unwrap(), catalog);
+    }
+
+    #[test]
+    fn removing_the_last_alias_keeps_detached_comments() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(
+            &path,
+            "ll = \"ls\"\n\n# --- git ---\n\ngs = \"git status\"\n",
+        )
+        .unwrap();
+        let mut catalog = load_catalog(&path).unwrap();
+        catalog.aliases.remove("gs");
+
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "ll = \"ls\"\n\n# --- git ---\n\n"
+        );
+    }
+
+    #[test]
+    fn sorting_is_optional() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(&path, "zz = \"last\"\n# first alias\naa = \"first\"\n").unwrap();
+        let mut catalog = load_catalog(&path).unwrap();
+
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().starts_with("zz"));
+
+        save_catalog(&mut catalog, &path, true, &catalog_stamp(&path)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# first alias\naa = \"first\"\nzz = \"last\"\n"
+        );
+    }
+
+    #[test]
+    fn sorting_keeps_the_version_first() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("aliases.toml");
+        fs::write(&path, "zz = \"last\"\nversion = 2\naa = \"first\"\n").unwrap();
+        let mut catalog = load_catalog(&path).unwrap();
+
+        save_catalog(&mut catalog, &path, true, &catalog_stamp(&path)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "version = 2\naa = \"first\"\nzz = \"last\"\n"
+        );
+    }
+
+    #[test]
+    fn version_key_is_not_an_alias() {
+        let catalog = parse_catalog("version = 2\nll = \"ls\"\n").unwrap();
+        assert_eq!(catalog.aliases.keys().collect::<Vec<_>>(), ["ll"]);
+
+        let catalog = parse_catalog("version = \"git --version\"\n").unwrap();
+        assert!(catalog.aliases.contains_key("version"));
+
+        let error = parse_catalog("version = 3\n").unwrap_err();
+        assert!(error.to_string().contains("requires a newer aliasmgr"));
+    }
+
     #[test]
     fn mixed_simple_and_detailed_aliases_load() {
         let directory = TempDir::new().unwrap();
@@ -179,7 +840,7 @@ mod tests {
         assert!(!catalog.aliases["ll"].detailed);
         assert!(catalog.aliases["test"].tags.contains("dev"));
 
-        save_catalog(&mut catalog, &path).unwrap();
+        save_catalog(&mut catalog, &path, false, &catalog_stamp(&path)).unwrap();
         assert_eq!(load_catalog(&path).unwrap(), catalog);
     }
 
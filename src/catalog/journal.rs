@@ -0,0 +1,123 @@
+//! Bounded journal of catalog changes used by undo, redo, and history.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::io::write_atomically;
+
+/// Number of changes kept; older entries are discarded first.
+pub const JOURNAL_LIMIT: usize = 50;
+
+/// One saved catalog change, stored as the full text before and after it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Command line that made the change.
+    pub command: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journal {
+    /// Number of entries currently applied; entries after it can be redone.
+    pub cursor: usize,
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Record a new change, discarding entries that were undone.
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.entries.truncate(self.cursor);
+        self.entries.push(entry);
+        if self.entries.len() > JOURNAL_LIMIT {
+            self.entries.drain(..self.entries.len() - JOURNAL_LIMIT);
+        }
+        self.cursor = self.entries.len();
+    }
+
+    pub fn undo_entry(&self) -> Option<&JournalEntry> {
+        self.cursor
+            .checked_sub(1)
+            .and_then(|index| self.entries.get(index))
+    }
+
+    pub fn redo_entry(&self) -> Option<&JournalEntry> {
+        self.entries.get(self.cursor)
+    }
+}
+
+/// The journal lives next to the catalog, e.g. `aliases.history.json`.
+pub fn journal_path(catalog_path: &Path) -> PathBuf {
+    catalog_path.with_extension("history.json")
+}
+
+pub fn load_journal(path: &Path) -> Result<Journal> {
+    if !path.exists() {
+        return Ok(Journal::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("could not read history '{}'", path.display()))?;
+    let mut journal: Journal = serde_json::from_str(&content)
+        .with_context(|| format!("could not parse history '{}'", path.display()))?;
+    journal.cursor = journal.cursor.min(journal.entries.len());
+    Ok(journal)
+}
+
+pub fn save_journal(path: &Path, journal: &Journal) -> Result<()> {
+    let content = serde_json::to_string_pretty(journal)?;
+    write_atomically(path, &content)
+        .with_context(|| format!("could not save history '{}'", path.display()))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    fn entry(command: &str) -> JournalEntry {
+        JournalEntry {
+            timestamp: 0,
+            command: command.into(),
+            before: String::new(),
+            after: command.into(),
+        }
+    }
+
+    #[test]
+    fn recording_discards_undone_entries_and_stays_bounded() {
+        let mut journal = Journal::default();
+        journal.record(entry("first"));
+        journal.record(entry("second"));
+        journal.cursor = 1;
+        assert_eq!(journal.redo_entry().unwrap().command, "second");
+
+        journal.record(entry("third"));
+        assert_eq!(journal.entries, [entry("first"), entry("third")]);
+        assert!(journal.redo_entry().is_none());
+
+        for index in 0..JOURNAL_LIMIT {
+            journal.record(entry(&index.to_string()));
+        }
+        assert_eq!(journal.entries.len(), JOURNAL_LIMIT);
+        assert_eq!(journal.entries[0].command, "0");
+        assert_eq!(journal.undo_entry().unwrap().command, "49");
+    }
+
+    #[test]
+    fn journal_round_trips_next_to_the_catalog() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = journal_path(&directory.path().join("aliases.toml"));
+        assert!(path.ends_with("aliases.history.json"));
+        assert_eq!(load_journal(&path).unwrap(), Journal::default());
+
+        let mut journal = Journal::default();
+        journal.record(entry("add ll ls"));
+        save_journal(&path, &journal).unwrap();
+        assert_eq!(load_journal(&path).unwrap(), journal);
+    }
+}
@@ -0,0 +1,251 @@
+//! Stack the system, user, and configured catalogs into one view.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use super::io::catalog_stamp;
+use super::types::AliasCatalog;
+
+pub const SYSTEM_CATALOG_ENV_VAR: &str = "ALIASMGR_SYSTEM_CATALOG_PATH";
+pub const SYSTEM_LAYER: &str = "system";
+pub const USER_LAYER: &str = "user";
+
+/// A named catalog file in the layer stack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogLayer {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+pub fn system_catalog_path() -> PathBuf {
+    env::var_os(SYSTEM_CATALOG_ENV_VAR).map_or_else(
+        || PathBuf::from("/etc/aliasmgr/aliases.toml"),
+        PathBuf::from,
+    )
+}
+
+/// The layers in override order: the system catalog, the user catalog, and
+/// then the configured layers.
+pub fn catalog_layers(user_path: &Path, configured: &[CatalogLayer]) -> Vec<CatalogLayer> {
+    [
+        CatalogLayer {
+            name: SYSTEM_LAYER.into(),
+            path: system_catalog_path(),
+        },
+        CatalogLayer {
+            name: USER_LAYER.into(),
+            path: user_path.to_path_buf(),
+        },
+    ]
+    .into_iter()
+    .chain(configured.iter().cloned())
+    .collect()
+}
+
+/// Merge layers so that later layers replace earlier aliases and variables of
+/// the same name.
+///
+/// When more than one layer is given, the merged catalog records which layer
+/// supplied each alias.
+pub fn merge_layers<'a>(layers: impl IntoIterator<Item = (&'a str, AliasCatalog)>) -> AliasCatalog {
+    let layers = layers.into_iter().collect::<Vec<_>>();
+    let record_sources = layers.len() > 1;
+    let mut merged = AliasCatalog::new();
+    for (name, catalog) in layers {
+        for (alias_name, alias) in catalog.aliases {
+            if record_sources {
+                merged.layers.insert(alias_name.clone(), name.to_owned());
+            }
+            merged.aliases.insert(alias_name, alias);
+        }
+        merged.variables.extend(catalog.variables);
+    }
+    merged
+}
+
+/// Describe every file that shapes the merged catalog, so the prompt hook can
+/// tell that nothing changed without reading the configuration.
+///
+/// The first line is the user catalog's stamp, followed by a tab and the
+/// project file in effect, if any. Then come `path<TAB>stamp` lines for the
+/// watched files that exist, such as the system catalog and configuration, for
+/// the project file, and for every configured layer.
+pub fn layers_stamp(
+    user_path: &Path,
+    watched: &[PathBuf],
+    configured: &[CatalogLayer],
+    project: Option<&Path>,
+) -> String {
+    let optional = watched
+        .iter()
+        .map(PathBuf::as_path)
+        .filter(|path| path.exists());
+    let files = optional
+        .chain(project)
+        .chain(configured.iter().map(|layer| layer.path.as_path()));
+    let mut stamp = catalog_stamp(user_path);
+    if let Some(project) = project {
+        stamp.push_str(&format!("\t{}", project.display()));
+    }
+    for path in files {
+        stamp.push_str(&format!("\n{}\t{}", path.display(), catalog_stamp(path)));
+    }
+    stamp
+}
+
+/// Whether a stamp from [`layers_stamp`] still describes the files on disk.
+pub fn is_layers_stamp_current(
+    stamp: &str,
+    user_path: &Path,
+    watched: &[PathBuf],
+    project: Option<&Path>,
+) -> bool {
+    let mut lines = stamp.lines();
+    let mut first = catalog_stamp(user_path);
+    if let Some(project) = project {
+        first.push_str(&format!("\t{}", project.display()));
+    }
+    if lines.next() != Some(first.as_str()) {
+        return false;
+    }
+    let mut listed = BTreeSet::new();
+    for line in lines {
+        let Some((path, file_stamp)) = line.split_once('\t') else {
+            return false;
+        };
+        if catalog_stamp(Path::new(path)) != file_stamp {
+            return false;
+        }
+        listed.insert(PathBuf::from(path));
+    }
+    watched
+        .iter()
+        .all(|path| !path.exists() || listed.contains(path))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::catalog::types::{Alias, AliasKind, Variable};
+
+    fn catalog(aliases: &[(&str, &str)]) -> AliasCatalog {
+        let mut catalog = AliasCatalog::new();
+        for (name, command) in aliases {
+            catalog.aliases.insert(
+                (*name).into(),
+                Alias::new((*command).into(), true, AliasKind::Regular),
+            );
+        }
+        catalog
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let merged = merge_layers([
+            ("system", catalog(&[("ll", "ls -l"), ("gs", "git status")])),
+            ("user", catalog(&[("ll", "ls -la")])),
+            ("work", catalog(&[("deploy", "make deploy")])),
+        ]);
+        assert_eq!(merged.aliases["ll"].command, "ls -la");
+        assert_eq!(merged.layers["ll"], "user");
+        assert_eq!(merged.layers["gs"], "system");
+        assert_eq!(merged.layers["deploy"], "work");
+
+        let mut system = catalog(&[]);
+        system
+            .variables
+            .insert("EDITOR".into(), Variable::new("vi".into(), true));
+        let mut user = catalog(&[]);
+        user.variables
+            .insert("EDITOR".into(), Variable::new("nvim".into(), true));
+        let merged = merge_layers([("system", system), ("user", user)]);
+        assert_eq!(merged.variables["EDITOR"].value, "nvim");
+
+        let single = merge_layers([("user", catalog(&[("ll", "ls")]))]);
+        assert!(single.layers.is_empty());
+    }
+
+    #[test]
+    fn layers_are_ordered_system_user_then_configured() {
+        let work = CatalogLayer {
+            name: "work".into(),
+            path: "/work/aliases.toml".into(),
+        };
+        let names = temp_env::with_var(SYSTEM_CATALOG_ENV_VAR, Some("/opt/aliases.toml"), || {
+            catalog_layers(Path::new("/home/aliases.toml"), std::slice::from_ref(&work))
+        });
+        assert_eq!(
+            names
+                .iter()
+                .map(|layer| (layer.name.as_str(), layer.path.to_str().unwrap()))
+                .collect::<Vec<_>>(),
+            [
+                ("system", "/opt/aliases.toml"),
+                ("user", "/home/aliases.toml"),
+                ("work", "/work/aliases.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn stamps_notice_changes_to_any_layer_or_the_configuration() {
+        let directory = tempfile::tempdir().unwrap();
+        let user = directory.path().join("aliases.toml");
+        let system = directory.path().join("system.toml");
+        let config = directory.path().join("config.toml");
+        let watched = [system.clone(), config.clone()];
+        let work = CatalogLayer {
+            name: "work".into(),
+            path: directory.path().join("work.toml"),
+        };
+        fs::write(&user, "ll = 'ls'\n").unwrap();
+        fs::write(&config, "").unwrap();
+
+        let stamp = layers_stamp(&user, &watched, std::slice::from_ref(&work), None);
+        assert!(is_layers_stamp_current(&stamp, &user, &watched, None));
+
+        fs::write(&work.path, "gs = 'git status'\n").unwrap();
+        assert!(!is_layers_stamp_current(&stamp, &user, &watched, None));
+
+        let stamp = layers_stamp(&user, &watched, std::slice::from_ref(&work), None);
+        fs::write(&system, "").unwrap();
+        assert!(!is_layers_stamp_current(&stamp, &user, &watched, None));
+
+        let stamp = layers_stamp(&user, &watched, &[], None);
+        assert!(is_layers_stamp_current(&stamp, &user, &watched, None));
+        fs::write(&config, "[catalog]\n").unwrap();
+        assert!(!is_layers_stamp_current(&stamp, &user, &watched, None));
+    }
+
+    #[test]
+    fn stamps_notice_entering_leaving_or_editing_a_project() {
+        let directory = tempfile::tempdir().unwrap();
+        let user = directory.path().join("aliases.toml");
+        let project = directory.path().join(".aliasmgr.toml");
+        fs::write(&project, "b = 'make build'\n").unwrap();
+
+        let outside = layers_stamp(&user, &[], &[], None);
+        assert!(!is_layers_stamp_current(
+            &outside,
+            &user,
+            &[],
+            Some(&project)
+        ));
+
+        let inside = layers_stamp(&user, &[], &[], Some(&project));
+        assert!(is_layers_stamp_current(&inside, &user, &[], Some(&project)));
+        assert!(!is_layers_stamp_current(&inside, &user, &[], None));
+
+        fs::write(&project, "b = 'make build-all'\n").unwrap();
+        assert!(!is_layers_stamp_current(
+            &inside,
+            &user,
+            &[],
+            Some(&project)
+        ));
+    }
+}
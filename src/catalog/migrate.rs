@@ -0,0 +1,264 @@
+//! Catalog format versions and migrations between them.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use toml_edit::{DocumentMut, Item, Key, value};
+
+use super::io::build_alias_value;
+use super::spec::{AliasSpecTypes, ENV_TABLE, convert_alias_spec};
+
+/// Catalog format written by this version of aliasmgr.
+pub const CATALOG_VERSION: i64 = 2;
+
+/// Top-level key recording the catalog format version.
+pub const VERSION_KEY: &str = "version";
+
+/// Migration steps in order; the step at index `n` upgrades version `n + 1`.
+const MIGRATIONS: [fn(&DocumentMut) -> Result<DocumentMut>; 1] = [ungroup_aliases];
+
+/// A catalog converted to the current format.
+pub struct Migration {
+    pub from_version: i64,
+    pub content: String,
+}
+
+/// Determine the catalog format version.
+///
+/// Catalogs without a version key are version 1 when they contain group
+/// tables other than `[env]` and current otherwise. An alias named `version`
+/// is never mistaken for the key because the key is an integer.
+pub fn catalog_version(document: &DocumentMut) -> i64 {
+    if let Some(version) = document.get(VERSION_KEY).and_then(Item::as_integer) {
+        return version;
+    }
+    if document
+        .iter()
+        .any(|(name, item)| item.is_table() && name != ENV_TABLE)
+    {
+        1
+    } else {
+        CATALOG_VERSION
+    }
+}
+
+/// Insert the current version key at the top of the document, unless an
+/// alias already uses the name.
+pub(super) fn set_catalog_version(document: &mut DocumentMut) {
+    match document.get_mut(VERSION_KEY).and_then(Item::as_value_mut) {
+        Some(version) if version.is_integer() => {
+            let decor = version.decor().clone();
+            *version = CATALOG_VERSION.into();
+            *version.decor_mut() = decor;
+        }
+        Some(_) => {}
+        None => {
+            let existing = std::mem::take(document.as_table_mut());
+            let root = document.as_table_mut();
+            root.insert(VERSION_KEY, value(CATALOG_VERSION));
+            for (index, (name, item)) in existing.iter().enumerate() {
+                let mut key = existing
+                    .key(name)
+                    .cloned()
+                    .unwrap_or_else(|| Key::new(name));
+                if index == 0 {
+                    let prefix = key.leaf_decor().prefix().and_then(|prefix| prefix.as_str());
+                    let prefix = format!("\n{}", prefix.unwrap_or_default());
+                    key.leaf_decor_mut().set_prefix(prefix);
+                }
+                root.insert_formatted(&key, item.clone());
+            }
+        }
+    }
+}
+
+/// Convert a catalog to the current format. Returns `None` when the catalog
+/// is already current and records its version.
+pub fn migrate_catalog(content: &str) -> Result<Option<Migration>> {
+    let mut document = content.parse::<DocumentMut>()?;
+    let from_version = catalog_version(&document);
+    if from_version > CATALOG_VERSION {
+        bail!(
+            "catalog format version {from_version} is newer than version {CATALOG_VERSION}, which this aliasmgr supports"
+        );
+    }
+    if from_version < 1 {
+        bail!("catalog format version {from_version} is invalid");
+    }
+    if from_version == CATALOG_VERSION && document.contains_key(VERSION_KEY) {
+        return Ok(None);
+    }
+    for step in &MIGRATIONS[from_version as usize - 1..] {
+        document = step(&document)?;
+    }
+    set_catalog_version(&mut document);
+    Ok(Some(Migration {
+        from_version,
+        content: document.to_string(),
+    }))
+}
+
+fn existing_key(document: &DocumentMut, name: &str) -> Key {
+    document
+        .key(name)
+        .cloned()
+        .unwrap_or_else(|| Key::new(name))
+}
+
+/// Tags cannot contain whitespace, so group names like `"my tools"` become `my-tools`.
+fn group_tag(group: &str) -> String {
+    group.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+/// Version 1 to 2: every group table becomes a tag on its aliases, and a
+/// disabled group disables each of its aliases.
+fn ungroup_aliases(document: &DocumentMut) -> Result<DocumentMut> {
+    let mut flat = DocumentMut::new();
+    let mut origins = BTreeMap::new();
+    let mut claim = |name: &str, origin: String| -> Result<()> {
+        if let Some(previous) = origins.insert(name.to_owned(), origin.clone()) {
+            bail!("alias '{name}' is defined in both {previous} and {origin}");
+        }
+        Ok(())
+    };
+
+    for (name, item) in document.iter() {
+        match item {
+            Item::Value(_) => {
+                claim(name, "the top level".into())?;
+                flat.insert_formatted(&existing_key(document, name), item.clone());
+            }
+            Item::Table(group) => {
+                let group_enabled = group.get("enabled").and_then(Item::as_bool).unwrap_or(true);
+                let tag = group_tag(name);
+                let mut header = group.decor().prefix().and_then(|prefix| prefix.as_str());
+                for (alias_name, alias_item) in group.iter() {
+                    if alias_name == "enabled" && alias_item.is_bool() {
+                        continue;
+                    }
+                    claim(alias_name, format!("group '{name}'"))?;
+                    let mut original = alias_item.clone().into_value().map_err(|_| {
+                        anyhow::anyhow!("group '{name}' has an invalid alias '{alias_name}'")
+                    })?;
+                    let decor = original.decor().clone();
+                    original.decor_mut().clear();
+                    let spec = AliasSpecTypes::deserialize(toml::de::ValueDeserializer::parse(
+                        &original.to_string(),
+                    )?)
+                    .with_context(|| {
+                        format!("group '{name}' has an invalid alias '{alias_name}'")
+                    })?;
+
+                    let mut alias = convert_alias_spec(spec).map_err(|reason| {
+                        anyhow::anyhow!(
+                            "group '{name}' has an invalid alias '{alias_name}': {reason}"
+                        )
+                    })?;
+                    alias.enabled &= group_enabled;
+                    if !tag.is_empty() {
+                        alias.tags.insert(tag.clone());
+                    }
+                    let mut converted = build_alias_value(&mut alias);
+                    *converted.decor_mut() = decor;
+
+                    let mut key = group
+                        .key(alias_name)
+                        .cloned()
+                        .unwrap_or_else(|| Key::new(alias_name));
+                    if let Some(header) = header.take() {
+                        let prefix = key.leaf_decor().prefix().and_then(|prefix| prefix.as_str());
+                        let mut prefix = format!("{header}{}", prefix.unwrap_or_default());
+                        if !flat.is_empty() && !prefix.starts_with('\n') {
+                            prefix.insert(0, '\n');
+                        }
+                        key.leaf_decor_mut().set_prefix(prefix);
+                    }
+                    flat.insert_formatted(&key, Item::Value(converted));
+                }
+            }
+            _ => bail!("'{name}' is neither an alias nor a group"),
+        }
+    }
+    flat.set_trailing(document.trailing().as_str().unwrap_or_default());
+    Ok(flat)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    fn migrate(content: &str) -> String {
+        migrate_catalog(content).unwrap().unwrap().content
+    }
+
+    #[test]
+    fn versions_are_inferred_when_missing() {
+        let version = |content: &str| catalog_version(&content.parse().unwrap());
+        assert_eq!(version(""), CATALOG_VERSION);
+        assert_eq!(version("ll = \"ls\"\n"), CATALOG_VERSION);
+        assert_eq!(version("version = \"git --version\"\n"), CATALOG_VERSION);
+        assert_eq!(version("[dev]\nbuild = \"cargo build\"\n"), 1);
+        assert_eq!(version("version = 3\n"), 3);
+    }
+
+    #[test]
+    fn groups_become_tags_and_fold_their_enabled_flag() {
+        let content = "\
+# Everyday aliases
+ll = \"ls -la\"
+
+# Rust tooling
+[dev]
+enabled = false
+build = \"cargo build\" # fast
+test = { command = \"cargo test\", tags = [\"rust\"] }
+
+[\"git tools\"]
+gs = \"git status\"
+";
+        assert_eq!(
+            migrate(content),
+            "\
+version = 2
+
+# Everyday aliases
+ll = \"ls -la\"
+
+# Rust tooling
+build = { command = \"cargo build\", enabled = false, global = false, tags = [\"dev\"] } # fast
+test = { command = \"cargo test\", enabled = false, global = false, tags = [\"dev\", \"rust\"] }
+
+gs = { command = \"git status\", enabled = true, global = false, tags = [\"git-tools\"] }
+"
+        );
+    }
+
+    #[test]
+    fn current_catalogs_only_gain_a_version() {
+        assert_eq!(migrate("ll = \"ls\"\n"), "version = 2\n\nll = \"ls\"\n");
+        assert!(
+            migrate_catalog("version = 2\nll = \"ls\"\n")
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            migrate_catalog("version = \"git --version\"\n")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn conflicting_and_newer_catalogs_are_rejected() {
+        let error = migrate_catalog("ll = \"ls\"\n[files]\nll = \"ls -la\"\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "alias 'll' is defined in both the top level and group 'files'"
+        );
+        assert!(migrate_catalog("version = 9\n").is_err());
+    }
+}
@@ -1,5 +1,10 @@
 //! Catalog loading, serialization, and data types.
 
 pub mod io;
+pub mod journal;
+pub mod layers;
+pub mod migrate;
+pub mod profile;
+pub mod project;
 pub mod spec;
 pub mod types;
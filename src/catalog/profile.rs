@@ -0,0 +1,54 @@
+//! The profile chosen with `aliasmgr profile use`, stored next to the catalog.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::io::write_atomically;
+
+/// The active profile lives next to the catalog, e.g. `aliases.profile`.
+pub fn profile_state_path(catalog_path: &Path) -> PathBuf {
+    catalog_path.with_extension("profile")
+}
+
+/// The persisted profile name, or `None` when no profile is in use.
+pub fn load_active_profile(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("could not read active profile '{}'", path.display()))?;
+    let name = content.trim();
+    Ok((!name.is_empty()).then(|| name.to_owned()))
+}
+
+pub fn save_active_profile(path: &Path, name: Option<&str>) -> Result<()> {
+    match name {
+        Some(name) => write_atomically(path, &format!("{name}\n")),
+        None if path.exists() => fs::remove_file(path),
+        None => Ok(()),
+    }
+    .with_context(|| format!("could not save active profile '{}'", path.display()))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_profile_round_trips_and_clears() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = profile_state_path(&directory.path().join("aliases.toml"));
+        assert!(path.ends_with("aliases.profile"));
+        assert_eq!(load_active_profile(&path).unwrap(), None);
+
+        save_active_profile(&path, Some("work")).unwrap();
+        assert_eq!(load_active_profile(&path).unwrap().as_deref(), Some("work"));
+
+        save_active_profile(&path, None).unwrap();
+        assert!(!path.exists());
+        save_active_profile(&path, None).unwrap();
+    }
+}
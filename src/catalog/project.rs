@@ -0,0 +1,111 @@
+//! Directory-scoped project catalogs and the record of trusted project files.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::io::write_atomically;
+
+pub const PROJECT_FILE_NAME: &str = ".aliasmgr.toml";
+pub const PROJECT_LAYER: &str = "project";
+
+/// The nearest project file in `directory` or one of its parents.
+pub fn find_project_file(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|ancestor| ancestor.join(PROJECT_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// SHA-256 of a project file's content, so that no other content can pass
+/// for the reviewed file.
+pub fn trust_digest(content: &str) -> String {
+    let hash = Sha256::digest(content.as_bytes());
+    let hex: String = hash.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256-{hex}")
+}
+
+/// Project files the user allowed to load, with the digest of the content
+/// that was reviewed. A project file that changed must be trusted again.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustStore {
+    pub projects: BTreeMap<PathBuf, String>,
+}
+
+impl TrustStore {
+    pub fn is_trusted(&self, path: &Path, digest: &str) -> bool {
+        self.projects
+            .get(path)
+            .is_some_and(|trusted| trusted == digest)
+    }
+}
+
+/// The trust store lives next to the catalog, e.g. `aliases.trust.json`.
+pub fn trust_store_path(catalog_path: &Path) -> PathBuf {
+    catalog_path.with_extension("trust.json")
+}
+
+pub fn load_trust_store(path: &Path) -> Result<TrustStore> {
+    if !path.exists() {
+        return Ok(TrustStore::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("could not read trusted projects '{}'", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("could not parse trusted projects '{}'", path.display()))
+}
+
+pub fn save_trust_store(path: &Path, store: &TrustStore) -> Result<()> {
+    let content = serde_json::to_string_pretty(store)?;
+    write_atomically(path, &content)
+        .with_context(|| format!("could not save trusted projects '{}'", path.display()))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_files_are_found_in_parent_directories() {
+        let directory = tempfile::tempdir().unwrap();
+        let nested = directory.path().join("src/bin");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_project_file(&nested), None);
+
+        let project = directory.path().join(PROJECT_FILE_NAME);
+        fs::write(&project, "").unwrap();
+        assert_eq!(find_project_file(&nested), Some(project));
+    }
+
+    #[test]
+    fn trust_is_tied_to_the_reviewed_content() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = trust_store_path(&directory.path().join("aliases.toml"));
+        assert!(path.ends_with("aliases.trust.json"));
+        assert_eq!(load_trust_store(&path).unwrap(), TrustStore::default());
+
+        let mut store = TrustStore::default();
+        store
+            .projects
+            .insert("/repo/.aliasmgr.toml".into(), "v1-1".into());
+        save_trust_store(&path, &store).unwrap();
+        let store = load_trust_store(&path).unwrap();
+        assert!(store.is_trusted(Path::new("/repo/.aliasmgr.toml"), "v1-1"));
+        assert!(!store.is_trusted(Path::new("/repo/.aliasmgr.toml"), "v1-2"));
+        assert!(!store.is_trusted(Path::new("/other/.aliasmgr.toml"), "v1-1"));
+    }
+
+    #[test]
+    fn trust_digests_are_sha256() {
+        assert_eq!(
+            trust_digest("abc"),
+            "sha256-ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(trust_digest("abc"), trust_digest("abd"));
+    }
+}
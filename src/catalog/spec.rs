@@ -4,15 +4,63 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
-use super::types::{Alias, AliasCatalog};
+use super::types::{Alias, AliasCatalog, AliasConditions, AliasKind, SHELL_COMMAND_KEYS, Variable};
+
+/// Top-level table holding environment variables instead of aliases.
+pub const ENV_TABLE: &str = "env";
 
 fn enabled_by_default() -> bool {
     true
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum CommandSpec {
+    Single(String),
+    /// The `default` command and commands for named shells.
+    PerShell(BTreeMap<String, String>),
+}
+
+/// Split a `command` table into the default command and the per-shell ones.
+pub fn split_command_table(
+    mut commands: BTreeMap<String, String>,
+) -> Result<(String, BTreeMap<String, String>), String> {
+    let default = commands
+        .remove("default")
+        .ok_or("command table needs a 'default' command")?;
+    if let Some(shell) = commands
+        .keys()
+        .find(|shell| !SHELL_COMMAND_KEYS.contains(&shell.as_str()))
+    {
+        return Err(format!("unknown shell '{shell}' in command table"));
+    }
+    Ok((default, commands))
+}
+
+/// Resolve an alias's kind from its `kind` key and the older `global` flag,
+/// which still marks global aliases on its own.
+pub fn resolve_alias_kind(kind: Option<&str>, global: bool) -> Result<AliasKind, String> {
+    let Some(kind) = kind else {
+        return Ok(if global {
+            AliasKind::Global
+        } else {
+            AliasKind::Regular
+        });
+    };
+    match AliasKind::parse(kind) {
+        None => Err(format!(
+            "unknown alias kind '{kind}'; expected regular, global, or suffix"
+        )),
+        Some(kind) if global && kind != AliasKind::Global => {
+            Err(format!("a {kind} alias cannot also be global"))
+        }
+        Some(kind) => Ok(kind),
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
 pub struct AliasSpec {
-    pub command: String,
+    pub command: CommandSpec,
 
     #[serde(default = "enabled_by_default")]
     pub enabled: bool,
@@ -20,11 +68,26 @@ pub struct AliasSpec {
     #[serde(default)]
     pub global: bool,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub function: bool,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub tags: BTreeSet<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub hosts: BTreeSet<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub os: BTreeSet<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub requires: BTreeSet<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
@@ -34,32 +97,98 @@ pub enum AliasSpecTypes {
     Detailed(AliasSpec),
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+pub struct VariableSpec {
+    pub value: String,
+
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum VariableSpecTypes {
+    Simple(String),
+    Detailed(VariableSpec),
+}
+
+/// The variables of the `[env]` table.
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvSpec {
+    #[serde(flatten)]
+    pub variables: BTreeMap<String, VariableSpecTypes>,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
 pub struct AliasCatalogSpec {
     #[serde(flatten)]
     pub aliases: BTreeMap<String, AliasSpecTypes>,
 }
 
-pub fn convert_spec_to_catalog(spec: AliasCatalogSpec) -> AliasCatalog {
+pub fn convert_alias_spec(spec: AliasSpecTypes) -> Result<Alias, String> {
+    match spec {
+        AliasSpecTypes::Simple(command) => Ok(Alias::new(command, true, AliasKind::Regular)),
+        AliasSpecTypes::Detailed(spec) => {
+            let (command, shell_commands) = match spec.command {
+                CommandSpec::Single(command) => (command, BTreeMap::new()),
+                CommandSpec::PerShell(commands) => split_command_table(commands)?,
+            };
+            let kind = resolve_alias_kind(spec.kind.as_deref(), spec.global)?;
+            if spec.function && kind != AliasKind::Regular {
+                return Err(format!("functions cannot be {kind} aliases"));
+            }
+            Ok(Alias {
+                command,
+                shell_commands,
+                enabled: spec.enabled,
+                kind,
+                function: spec.function,
+                description: spec.description,
+                tags: spec.tags,
+                conditions: AliasConditions {
+                    hosts: spec.hosts,
+                    os: spec.os,
+                    requires: spec.requires,
+                },
+                detailed: true,
+            })
+        }
+    }
+}
+
+pub fn convert_variable_spec(spec: VariableSpecTypes) -> Variable {
+    match spec {
+        VariableSpecTypes::Simple(value) => Variable::new(value, true),
+        VariableSpecTypes::Detailed(spec) => Variable {
+            value: spec.value,
+            enabled: spec.enabled,
+            description: spec.description,
+            tags: spec.tags,
+            detailed: true,
+        },
+    }
+}
+
+pub fn convert_spec_to_catalog(spec: AliasCatalogSpec) -> Result<AliasCatalog, String> {
     let aliases = spec
         .aliases
         .into_iter()
         .map(|(name, spec)| {
-            let alias = match spec {
-                AliasSpecTypes::Simple(command) => Alias::new(command, true, false),
-                AliasSpecTypes::Detailed(spec) => Alias {
-                    command: spec.command,
-                    enabled: spec.enabled,
-                    global: spec.global,
-                    description: spec.description,
-                    tags: spec.tags,
-                    detailed: true,
-                },
-            };
-            (name, alias)
+            convert_alias_spec(spec)
+                .map(|alias| (name.clone(), alias))
+                .map_err(|reason| format!("alias '{name}': {reason}"))
         })
-        .collect();
-    AliasCatalog { aliases }
+        .collect::<Result<_, _>>()?;
+    Ok(AliasCatalog {
+        aliases,
+        ..AliasCatalog::default()
+    })
 }
 
 #[cfg(test)]
@@ -73,10 +202,14 @@ mod tests {
             r#"
             ll = "ls -la"
             test = { command = "cargo test", description = "Run tests", tags = ["dev", "rust"] }
+            o = { command = "xdg-open", os = ["linux"], requires = ["xdg-open"] }
+            reload = { command.default = "exec $SHELL", command.fish = "exec fish" }
+            G = { command = "| grep", global = true }
+            rs = { command = "vim", kind = "suffix" }
             "#,
         )
         .unwrap();
-        let catalog = convert_spec_to_catalog(spec);
+        let catalog = convert_spec_to_catalog(spec).unwrap();
 
         assert!(!catalog.aliases["ll"].detailed);
         assert_eq!(
@@ -91,5 +224,48 @@ mod tests {
                 .collect::<Vec<_>>(),
             ["dev", "rust"]
         );
+        let conditions = &catalog.aliases["o"].conditions;
+        assert!(conditions.os.contains("linux") && conditions.requires.contains("xdg-open"));
+        assert!(conditions.hosts.is_empty());
+        let reload = &catalog.aliases["reload"];
+        assert_eq!(reload.command, "exec $SHELL");
+        assert_eq!(reload.shell_commands["fish"], "exec fish");
+        assert!(reload.detailed);
+        assert_eq!(catalog.aliases["G"].kind, AliasKind::Global);
+        assert_eq!(catalog.aliases["rs"].kind, AliasKind::Suffix);
+        assert_eq!(catalog.aliases["ll"].kind, AliasKind::Regular);
+    }
+
+    #[test]
+    fn command_tables_need_a_default_and_known_shells() {
+        for (source, reason) in [
+            (
+                "x = { command.bash = 'x' }",
+                "alias 'x': command table needs a 'default' command",
+            ),
+            (
+                "x = { command.default = 'x', command.tcsh = 'y' }",
+                "alias 'x': unknown shell 'tcsh' in command table",
+            ),
+            (
+                "x = { command = 'x', function = true, global = true }",
+                "alias 'x': functions cannot be global aliases",
+            ),
+            (
+                "x = { command = 'x', function = true, kind = 'suffix' }",
+                "alias 'x': functions cannot be suffix aliases",
+            ),
+            (
+                "x = { command = 'x', kind = 'prefix' }",
+                "alias 'x': unknown alias kind 'prefix'; expected regular, global, or suffix",
+            ),
+            (
+                "x = { command = 'x', kind = 'suffix', global = true }",
+                "alias 'x': a suffix alias cannot also be global",
+            ),
+        ] {
+            let spec: AliasCatalogSpec = toml::from_str(source).unwrap();
+            assert_eq!(convert_spec_to_catalog(spec).err().unwrap(), reason);
+        }
     }
 }
@@ -2,33 +2,136 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 
+/// Machines an alias applies to. Each non-empty list must be satisfied.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct AliasConditions {
+    /// Host names, matched case-insensitively with or without the domain.
+    pub hosts: BTreeSet<String>,
+    /// Operating systems as named by Rust, such as `linux` or `macos`.
+    pub os: BTreeSet<String>,
+    /// Executables that must be found on `PATH`.
+    pub requires: BTreeSet<String>,
+}
+
+impl AliasConditions {
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty() && self.os.is_empty() && self.requires.is_empty()
+    }
+}
+
+/// Shells that can have their own command through `command.<shell>`.
+pub const SHELL_COMMAND_KEYS: [&str; 5] = ["bash", "zsh", "fish", "nu", "posix"];
+
+/// How a shell expands an alias.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum AliasKind {
+    /// Expands as the first word of a command.
+    #[default]
+    Regular,
+    /// Zsh global alias, which expands anywhere in a command line.
+    Global,
+    /// Zsh suffix alias, which opens files ending in `.<name>` with the
+    /// command when such a file is typed as a command.
+    Suffix,
+}
+
+impl AliasKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Regular => "regular",
+            Self::Global => "global",
+            Self::Suffix => "suffix",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::Regular, Self::Global, Self::Suffix]
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+    }
+
+    /// Whether the alias can run as a command name, and so shadow a builtin or
+    /// executable of the same name. Suffix aliases only match file names.
+    pub fn takes_command_position(self) -> bool {
+        self != Self::Suffix
+    }
+}
+
+impl std::fmt::Display for AliasKind {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
 /// Representation of an alias in the catalog.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Alias {
     pub command: String,
+    /// Commands that replace `command` in one shell, keyed by a
+    /// [`SHELL_COMMAND_KEYS`] entry.
+    pub shell_commands: BTreeMap<String, String>,
     pub enabled: bool,
-    pub global: bool,
+    pub kind: AliasKind,
+    /// Whether the command is a function body that receives the arguments as
+    /// `$1`, `$@`, and so on, instead of text the alias expands to.
+    pub function: bool,
     pub description: Option<String>,
     pub tags: BTreeSet<String>,
+    pub conditions: AliasConditions,
     // Keeps track of whether the alias uses detailed representation.
     pub detailed: bool,
 }
 
 impl Alias {
-    pub fn new(command: String, enabled: bool, global: bool) -> Self {
+    pub fn new(command: String, enabled: bool, kind: AliasKind) -> Self {
         Self {
             command,
+            shell_commands: BTreeMap::new(),
             enabled,
-            global,
+            kind,
+            function: false,
             description: None,
             tags: BTreeSet::new(),
-            detailed: !enabled || global,
+            conditions: AliasConditions::default(),
+            detailed: !enabled || kind != AliasKind::Regular,
         }
     }
 
     pub fn refresh_representation(&mut self) {
-        self.detailed =
-            !self.enabled || self.global || self.description.is_some() || !self.tags.is_empty();
+        self.detailed = !self.enabled
+            || self.kind != AliasKind::Regular
+            || self.function
+            || !self.shell_commands.is_empty()
+            || self.description.is_some()
+            || !self.tags.is_empty()
+            || !self.conditions.is_empty();
+    }
+}
+
+/// An environment variable that shells export while it is enabled.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Variable {
+    pub value: String,
+    pub enabled: bool,
+    pub description: Option<String>,
+    pub tags: BTreeSet<String>,
+    // Keeps track of whether the variable uses detailed representation.
+    pub detailed: bool,
+}
+
+impl Variable {
+    pub fn new(value: String, enabled: bool) -> Self {
+        Self {
+            value,
+            enabled,
+            description: None,
+            tags: BTreeSet::new(),
+            detailed: !enabled,
+        }
+    }
+
+    pub fn refresh_representation(&mut self) {
+        self.detailed = !self.enabled || self.description.is_some() || !self.tags.is_empty();
     }
 }
 
@@ -36,12 +139,29 @@ impl Alias {
 #[derive(PartialEq, Eq, Debug, Default, Clone)]
 pub struct AliasCatalog {
     pub aliases: BTreeMap<String, Alias>,
+    /// Environment variables from the catalog's `[env]` table.
+    pub variables: BTreeMap<String, Variable>,
+    /// Layer that supplied each alias when several catalog layers are merged;
+    /// empty for a catalog loaded from one file.
+    pub layers: BTreeMap<String, String>,
+    /// Why aliases do not apply in this terminal, such as an unmet condition
+    /// or the active profile; filled only for commands that evaluate them.
+    pub inactive: BTreeMap<String, String>,
 }
 
 impl AliasCatalog {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Disable the aliases recorded as inactive, so shells skip them.
+    pub fn disable_inactive(&mut self) {
+        for name in self.inactive.keys() {
+            if let Some(alias) = self.aliases.get_mut(name) {
+                alias.enabled = false;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -51,11 +171,27 @@ mod tests {
 
     #[test]
     fn metadata_requires_detailed_representation() {
-        let mut alias = Alias::new("cmd".into(), true, false);
+        let mut alias = Alias::new("cmd".into(), true, AliasKind::Regular);
         assert!(!alias.detailed);
 
         alias.tags.insert("dev".into());
         alias.refresh_representation();
         assert!(alias.detailed);
+
+        let mut alias = Alias::new("cmd".into(), true, AliasKind::Regular);
+        alias.conditions.os.insert("linux".into());
+        alias.refresh_representation();
+        assert!(alias.detailed);
+    }
+
+    #[test]
+    fn kinds_parse_their_names() {
+        for kind in [AliasKind::Regular, AliasKind::Global, AliasKind::Suffix] {
+            assert_eq!(AliasKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(AliasKind::parse("Global"), None);
+        assert!(Alias::new("vim".into(), true, AliasKind::Suffix).detailed);
+        assert!(AliasKind::Global.takes_command_position());
+        assert!(!AliasKind::Suffix.takes_command_position());
     }
 }
@@ -7,8 +7,14 @@ pub struct AddCommand {
     pub name: String,
     pub command: String,
     /// Create a Zsh global alias
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "function")]
     pub global: bool,
+    /// Create a Zsh suffix alias, which opens files ending in `.NAME` with the command
+    #[arg(short, long, conflicts_with_all = ["global", "function"])]
+    pub suffix: bool,
+    /// Define a shell function whose command reads its arguments as `$1`, `$@`
+    #[arg(short = 'f', long)]
+    pub function: bool,
     /// Add a tag; repeat to add multiple tags
     #[arg(short, long, value_name = "TAG", value_parser = validate_tag)]
     pub tag: Vec<String>,
@@ -33,6 +33,22 @@ pub struct EditCommand {
     /// Make the alias non-global
     #[arg(long)]
     pub no_global: bool,
+
+    /// Make the alias a Zsh suffix alias
+    #[arg(short, long, conflicts_with_all = ["no_suffix", "global"])]
+    pub suffix: bool,
+
+    /// Make the suffix alias a regular alias
+    #[arg(long)]
+    pub no_suffix: bool,
+
+    /// Define the alias as a shell function
+    #[arg(short, long, conflicts_with = "no_function")]
+    pub function: bool,
+
+    /// Define the alias as a plain alias again
+    #[arg(long)]
+    pub no_function: bool,
 }
 
 impl EditCommand {
@@ -44,6 +60,10 @@ impl EditCommand {
             || !self.remove_tag.is_empty()
             || self.global
             || self.no_global
+            || self.suffix
+            || self.no_suffix
+            || self.function
+            || self.no_function
     }
 }
 
@@ -62,6 +82,10 @@ mod tests {
             remove_tag: vec![],
             global: false,
             no_global: false,
+            suffix: false,
+            no_suffix: false,
+            function: false,
+            no_function: false,
         }
     }
 
@@ -90,6 +114,18 @@ mod tests {
         let mut value = command();
         value.no_global = true;
         variants.push(value);
+        let mut value = command();
+        value.suffix = true;
+        variants.push(value);
+        let mut value = command();
+        value.no_suffix = true;
+        variants.push(value);
+        let mut value = command();
+        value.function = true;
+        variants.push(value);
+        let mut value = command();
+        value.no_function = true;
+        variants.push(value);
         assert!(variants.iter().all(EditCommand::has_changes));
     }
 }
@@ -0,0 +1,48 @@
+use clap::{Args, Subcommand};
+
+use super::validate_tag;
+
+#[derive(Args)]
+pub struct EnvCommand {
+    #[command(subcommand)]
+    pub action: EnvAction,
+}
+
+#[derive(Subcommand)]
+pub enum EnvAction {
+    /// List the catalog's environment variables
+    #[command(visible_alias = "ls")]
+    List {
+        /// Select variables containing every supplied tag
+        #[arg(short, long, value_name = "TAG", value_parser = validate_tag)]
+        tag: Vec<String>,
+    },
+    /// Export a variable in every terminal, replacing its current definition
+    Set {
+        name: String,
+        value: String,
+        /// Add a tag; repeat to add multiple tags
+        #[arg(short, long, value_name = "TAG", value_parser = validate_tag)]
+        tag: Vec<String>,
+        /// Describe what the variable is for
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Create the variable in a disabled state
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// Remove a variable, restoring the value terminals had before
+    #[command(visible_alias = "rm")]
+    Remove { name: String },
+    /// Export a disabled variable again
+    Enable { name: String },
+    /// Stop exporting a variable without removing it
+    Disable { name: String },
+}
+
+impl EnvAction {
+    /// Whether the action can change the catalog.
+    pub fn modifies_catalog(&self) -> bool {
+        !matches!(self, Self::List { .. })
+    }
+}
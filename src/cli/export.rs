@@ -0,0 +1,28 @@
+use clap::{Args, ValueEnum};
+
+use super::list::AliasSelection;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(alias = "nushell")]
+    Nu,
+    #[value(aliases = ["sh", "dash", "ksh"])]
+    Posix,
+    Json,
+    Toml,
+}
+
+#[derive(Args)]
+pub struct ExportCommand {
+    #[command(flatten)]
+    pub selection: AliasSelection,
+    /// Select a shell script, JSON, or a TOML catalog
+    #[arg(short, long, value_enum)]
+    pub format: ExportFormat,
+    /// Write alias descriptions as comments in shell scripts
+    #[arg(long)]
+    pub descriptions: bool,
+}
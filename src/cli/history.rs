@@ -0,0 +1,10 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct UndoCommand {}
+
+#[derive(Args)]
+pub struct RedoCommand {}
+
+#[derive(Args)]
+pub struct HistoryCommand {}
@@ -1,24 +1,80 @@
 use std::path::PathBuf;
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 
+use super::list::OutputFormat;
 use super::validate_tag;
+use crate::core::validation::is_valid_alias_name;
+
+/// Shells whose defined aliases can be listed for import.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AliasListingShell {
+    Bash,
+    Zsh,
+}
+
+/// How import sources are read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// Read JSON alias lists as JSON and anything else as shell declarations
+    #[default]
+    Auto,
+    /// Bash or Zsh alias declarations
+    Shell,
+    /// Alias lists written by `list --format json` or `export --format json`
+    Json,
+}
+
+fn validate_rename_suffix(suffix: &str) -> Result<String, String> {
+    if is_valid_alias_name(suffix) {
+        Ok(suffix.to_owned())
+    } else {
+        Err("suffixes must not be empty or contain whitespace or '='".into())
+    }
+}
 
 #[derive(Args)]
 pub struct ImportCommand {
-    /// Bash or Zsh files containing alias declarations
-    #[arg(required = true)]
+    /// Bash or Zsh files or JSON alias lists; use - for standard input
+    #[arg(required_unless_present = "from_shell", conflicts_with = "from_shell")]
     pub paths: Vec<PathBuf>,
+    /// Import the aliases defined by a new interactive Bash or Zsh shell
+    #[arg(long, value_enum, value_name = "SHELL")]
+    pub from_shell: Option<AliasListingShell>,
+    /// Select how files are read
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value = "auto",
+        conflicts_with = "from_shell"
+    )]
+    pub format: ImportFormat,
     /// Report what would happen without changing the catalog
     #[arg(short, long)]
     pub dry_run: bool,
+    /// Print a summary or a JSON report of every imported line
+    #[arg(short, long, value_enum, default_value = "human")]
+    pub output_format: OutputFormat,
     /// Keep catalog entries when imported aliases have the same names
     #[arg(short, long, conflicts_with = "replace_existing")]
     pub skip_existing: bool,
     /// Replace catalog entries when imported aliases have the same names
     #[arg(short, long, conflicts_with = "skip_existing")]
     pub replace_existing: bool,
+    /// Take the imported command but keep the catalog's description and tags
+    #[arg(short, long, conflicts_with_all = ["skip_existing", "replace_existing"])]
+    pub merge_existing: bool,
+    /// Import colliding aliases that are not replaced under their name plus SUFFIX
+    #[arg(long, value_name = "SUFFIX", value_parser = validate_rename_suffix, allow_hyphen_values = true, conflicts_with_all = ["replace_existing", "merge_existing"])]
+    pub rename_suffix: Option<String>,
     /// Add a tag to every imported alias; repeat to add multiple tags
     #[arg(short, long, value_name = "TAG", value_parser = validate_tag)]
     pub tag: Vec<String>,
+    /// Use each alias's trailing comment as its description
+    #[arg(long)]
+    pub comment_descriptions: bool,
+    /// Tag each alias with the section header comment above it, such as ## Git
+    #[arg(long)]
+    pub section_tags: bool,
 }
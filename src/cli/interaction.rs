@@ -49,11 +49,13 @@ pub fn prompt_overwrite_existing_alias(mode: InteractionMode, alias: &str) -> bo
 }
 
 #[cfg_attr(coverage_nightly, coverage(off))]
-pub fn prompt_replace_imported_alias(mode: InteractionMode, alias: &str) -> bool {
+pub fn prompt_replace_imported_alias(mode: InteractionMode, alias: &str, comparison: &str) -> bool {
     confirm(
         mode,
         "replace an existing alias during import",
-        format!("Alias \"{alias}\" already exists. Replace it with the imported alias?"),
+        format!(
+            "Alias \"{alias}\" already exists.\n{comparison}\nReplace it with the imported alias?"
+        ),
         false,
     )
 }
@@ -68,6 +70,16 @@ pub fn prompt_use_non_existing_catalog_file(mode: InteractionMode, path: &str) -
     )
 }
 
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn prompt_migrate_catalog(mode: InteractionMode) -> bool {
+    confirm(
+        mode,
+        "migrate the catalog",
+        "Save the migrated catalog? The original will be kept as a backup.".into(),
+        true,
+    )
+}
+
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub fn prompt_confirm_remove_all(mode: InteractionMode) -> bool {
     confirm(
@@ -16,9 +16,14 @@ pub enum ListColumn {
     Status,
     Name,
     Command,
-    Global,
+    // Shows Zsh global and suffix aliases; `global` is the column's older name.
+    #[serde(alias = "global")]
+    #[value(alias = "global")]
+    Kind,
     Tags,
     Description,
+    Layer,
+    Inactive,
 }
 
 impl ListColumn {
@@ -26,28 +31,38 @@ impl ListColumn {
         Self::Status,
         Self::Name,
         Self::Command,
-        Self::Global,
+        Self::Kind,
         Self::Tags,
         Self::Description,
     ];
 }
 
+/// Alias filters shared by `list` and `export`.
 #[derive(Args)]
-#[command(group(ArgGroup::new("list_scope").args(["disabled", "all"]).multiple(false)))]
-pub struct ListCommand {
+#[command(group(ArgGroup::new("alias_scope").args(["disabled", "all"]).multiple(false)))]
+pub struct AliasSelection {
     pub pattern: Option<String>,
-    /// List aliases containing every supplied tag
+    /// Select aliases containing every supplied tag
     #[arg(short, long, value_name = "TAG", value_parser = validate_tag)]
     pub tag: Vec<String>,
-    /// List only disabled aliases
+    /// Select only disabled aliases
     #[arg(short = 'd', long)]
     pub disabled: bool,
-    /// List enabled and disabled aliases
+    /// Select enabled and disabled aliases
     #[arg(long)]
     pub all: bool,
-    /// List only Zsh global aliases
-    #[arg(short = 'g', long)]
+    /// Select only Zsh global aliases
+    #[arg(short = 'g', long, conflicts_with = "suffix")]
     pub global: bool,
+    /// Select only Zsh suffix aliases
+    #[arg(short = 's', long)]
+    pub suffix: bool,
+}
+
+#[derive(Args)]
+pub struct ListCommand {
+    #[command(flatten)]
+    pub selection: AliasSelection,
     /// Override configured table columns, in display order
     #[arg(long, value_enum, value_delimiter = ',', num_args = 1..)]
     pub columns: Option<Vec<ListColumn>>,
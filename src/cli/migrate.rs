@@ -0,0 +1,8 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct MigrateCommand {
+    /// Show the converted catalog as a diff without saving it
+    #[arg(short, long)]
+    pub dry_run: bool,
+}
@@ -5,14 +5,21 @@ pub(crate) mod disable;
 pub(crate) mod doctor;
 pub(crate) mod edit;
 pub(crate) mod enable;
+pub(crate) mod env;
+pub(crate) mod export;
+pub(crate) mod history;
 pub(crate) mod import;
 pub(crate) mod init;
 pub(crate) mod interaction;
 pub(crate) mod list;
+pub(crate) mod migrate;
+pub(crate) mod profile;
 pub(crate) mod remove;
 pub(crate) mod rename;
 pub(crate) mod selector;
+pub(crate) mod status;
 pub(crate) mod sync;
+pub(crate) mod trust;
 
 use crate::config::ColorMode;
 use add::AddCommand;
@@ -20,12 +27,19 @@ use disable::DisableCommand;
 use doctor::DoctorCommand;
 use edit::EditCommand;
 use enable::EnableCommand;
+use env::{EnvAction, EnvCommand};
+use export::ExportCommand;
+use history::{HistoryCommand, RedoCommand, UndoCommand};
 use import::ImportCommand;
 use init::InitCommand;
 use list::ListCommand;
+use migrate::MigrateCommand;
+use profile::ProfileCommand;
 use remove::RemoveCommand;
 use rename::RenameCommand;
+use status::StatusCommand;
 use sync::{ShellSyncCommand, SyncCommand};
+use trust::{TrustCommand, UntrustCommand};
 
 pub fn validate_tag(tag: &str) -> Result<String, String> {
     if crate::core::validation::is_valid_tag(tag) {
@@ -70,6 +84,15 @@ pub struct Cli {
     /// Never prompt; fail when confirmation is required
     #[arg(short = 'N', long, global = true, conflicts_with_all = ["yes", "no"], help_heading = "Global Options")]
     pub no_input: bool,
+    /// Catalog layer that commands change: system, user, or a configured layer
+    #[arg(
+        long,
+        global = true,
+        value_name = "LAYER",
+        default_value = "user",
+        help_heading = "Global Options"
+    )]
+    pub layer: String,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -111,6 +134,13 @@ impl Cli {
                 "--no cannot be used with --replace-existing",
             ));
         }
+        if self.yes && matches!(&self.command, Commands::Import(cmd) if cmd.rename_suffix.is_some())
+        {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                "--yes cannot be used with --rename-suffix",
+            ));
+        }
         if matches!(&self.command, Commands::Edit(cmd) if !cmd.has_changes()) {
             return Err(Self::command().error(
                 ErrorKind::MissingRequiredArgument,
@@ -147,17 +177,72 @@ pub enum Commands {
     /// Edit an alias command or metadata
     #[command(visible_alias = "ed")]
     Edit(EditCommand),
-    /// Import aliases from Bash or Zsh files
+    /// Import aliases from Bash or Zsh files, JSON alias lists, standard input, or a live shell
     #[command(visible_alias = "im")]
     Import(ImportCommand),
+    /// Write aliases as a standalone shell script, JSON, or TOML
+    #[command(visible_alias = "ex")]
+    Export(ExportCommand),
+    /// Revert the most recent catalog change
+    Undo(UndoCommand),
+    /// Reapply the most recently undone catalog change
+    Redo(RedoCommand),
+    /// Show recent catalog changes
+    History(HistoryCommand),
+    /// Convert the catalog from an older format, keeping a backup
+    Migrate(MigrateCommand),
+    /// Allow a project's .aliasmgr.toml to define aliases inside its directory
+    Trust(TrustCommand),
+    /// Stop loading a project's .aliasmgr.toml
+    Untrust(UntrustCommand),
+    /// Switch between named sets of active aliases
+    Profile(ProfileCommand),
+    /// Manage environment variables that terminals export
+    Env(EnvCommand),
     /// Synchronize aliases with the catalog
     Sync(SyncCommand),
+    /// Compare this terminal's aliases with the catalog revision
+    #[command(visible_alias = "st")]
+    Status(StatusCommand),
     #[command(hide = true)]
     ShellSync(ShellSyncCommand),
     #[command(hide = true)]
     Init(InitCommand),
 }
 
+impl Commands {
+    /// Whether the command can change the catalog and must hold its lock.
+    pub fn modifies_catalog(&self) -> bool {
+        matches!(
+            self,
+            Self::Add(_)
+                | Self::Remove(_)
+                | Self::Enable(_)
+                | Self::Disable(_)
+                | Self::Rename(_)
+                | Self::Edit(_)
+                | Self::Import(_)
+                | Self::Undo(_)
+                | Self::Redo(_)
+                | Self::Migrate(_)
+        ) || matches!(self, Self::Env(cmd) if cmd.action.modifies_catalog())
+    }
+
+    /// Whether the command works on every catalog layer merged together.
+    pub fn reads_all_layers(&self) -> bool {
+        matches!(
+            self,
+            Self::List(_)
+                | Self::Export(_)
+                | Self::Status(_)
+                | Self::ShellSync(_)
+                | Self::Env(EnvCommand {
+                    action: EnvAction::List { .. }
+                })
+        )
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
@@ -165,6 +250,7 @@ mod tests {
     use std::path::{Path, PathBuf};
 
     use super::*;
+    use crate::cli::import::AliasListingShell;
 
     #[test]
     fn parses_repeated_add_tags() {
@@ -276,12 +362,19 @@ mod tests {
         assert_eq!(
             options(&mut command, "Global Options"),
             [
-                "color", "quiet", "verbose", "debug", "yes", "no", "no-input"
+                "color", "quiet", "verbose", "debug", "yes", "no", "no-input", "layer"
             ]
         );
         assert_eq!(
             options(command.find_subcommand_mut("add").unwrap(), "Options"),
-            ["global", "tag", "description", "disabled"]
+            [
+                "global",
+                "suffix",
+                "function",
+                "tag",
+                "description",
+                "disabled"
+            ]
         );
         assert_eq!(
             options(command.find_subcommand_mut("edit").unwrap(), "Options"),
@@ -292,15 +385,45 @@ mod tests {
                 "clear-description",
                 "global",
                 "no-global",
+                "suffix",
+                "no-suffix",
+                "function",
+                "no-function",
             ]
         );
         assert_eq!(
             options(command.find_subcommand_mut("import").unwrap(), "Options"),
-            ["dry-run", "skip-existing", "replace-existing", "tag"]
+            [
+                "from-shell",
+                "format",
+                "dry-run",
+                "output-format",
+                "skip-existing",
+                "replace-existing",
+                "merge-existing",
+                "rename-suffix",
+                "tag",
+                "comment-descriptions",
+                "section-tags"
+            ]
         );
         assert_eq!(
             options(command.find_subcommand_mut("list").unwrap(), "Options"),
-            ["tag", "disabled", "all", "global", "columns", "format"]
+            [
+                "tag", "disabled", "all", "global", "suffix", "columns", "format"
+            ]
+        );
+        assert_eq!(
+            options(command.find_subcommand_mut("export").unwrap(), "Options"),
+            [
+                "tag",
+                "disabled",
+                "all",
+                "global",
+                "suffix",
+                "format",
+                "descriptions"
+            ]
         );
         assert_eq!(
             options(
@@ -326,6 +449,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn import_reads_either_paths_or_a_live_shell() {
+        let cli = Cli::try_parse_from(["aliasmgr", "import", "--from-shell", "zsh"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Import(ImportCommand { paths, from_shell: Some(AliasListingShell::Zsh), .. }) if paths.is_empty()
+        ));
+        assert!(Cli::try_parse_from(["aliasmgr", "import"]).is_err());
+        assert!(
+            Cli::try_parse_from(["aliasmgr", "import", ".zshrc", "--from-shell", "zsh"]).is_err()
+        );
+    }
+
     #[test]
     fn flags_have_help_and_unique_short_options_in_every_context() {
         fn assert_command(command: &Command) {
@@ -408,11 +544,22 @@ mod tests {
         assert_eq!(short(list, "tag"), Some('t'));
         assert!(short(list, "enabled").is_none());
 
+        let export = command.find_subcommand("export").unwrap();
+        assert_eq!(short(export, "descriptions"), None);
+        assert_eq!(short(export, "format"), Some('f'));
+
         let import = command.find_subcommand("import").unwrap();
         assert_eq!(short(import, "dry-run"), Some('d'));
+        assert_eq!(short(import, "from-shell"), None);
+        assert_eq!(short(import, "format"), Some('f'));
+        assert_eq!(short(import, "output-format"), Some('o'));
         assert_eq!(short(import, "skip-existing"), Some('s'));
         assert_eq!(short(import, "replace-existing"), Some('r'));
+        assert_eq!(short(import, "merge-existing"), Some('m'));
+        assert_eq!(short(import, "rename-suffix"), None);
         assert_eq!(short(import, "tag"), Some('t'));
+        assert_eq!(short(import, "comment-descriptions"), None);
+        assert_eq!(short(import, "section-tags"), None);
 
         let remove_alias = command
             .find_subcommand("remove")
@@ -435,10 +582,31 @@ mod tests {
             ])
             .is_err()
         );
+        for policies in [
+            ["--merge-existing", "--skip-existing"],
+            ["--merge-existing", "--replace-existing"],
+            ["--rename-suffix=-new", "--merge-existing"],
+            ["--rename-suffix=-new", "--replace-existing"],
+        ] {
+            let args = ["aliasmgr", "import", ".zshrc", policies[0], policies[1]];
+            assert!(Cli::try_parse_from(args).is_err(), "{policies:?}");
+        }
+        assert!(
+            Cli::try_parse_from(["aliasmgr", "import", ".zshrc", "--rename-suffix", "a b"])
+                .is_err()
+        );
 
         for args in [
             &["aliasmgr", "--yes", "import", ".zshrc", "--skip-existing"][..],
             &["aliasmgr", "--no", "import", ".zshrc", "--replace-existing"][..],
+            &[
+                "aliasmgr",
+                "--yes",
+                "import",
+                ".zshrc",
+                "--rename-suffix",
+                "-new",
+            ][..],
         ] {
             let cli = Cli::try_parse_from(args).unwrap();
             assert_eq!(
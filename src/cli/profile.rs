@@ -0,0 +1,23 @@
+use clap::{Args, Subcommand};
+
+use super::validate_tag;
+
+#[derive(Args)]
+pub struct ProfileCommand {
+    #[command(subcommand)]
+    pub action: ProfileAction,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// List configured profiles and mark the active one
+    #[command(visible_alias = "ls")]
+    List,
+    /// Activate a profile in every terminal
+    Use {
+        #[arg(value_parser = validate_tag)]
+        name: String,
+    },
+    /// Stop using a profile so every enabled alias is active
+    Clear,
+}
@@ -0,0 +1,11 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct StatusCommand {
+    /// Print only the catalog revision for the current shell
+    #[arg(long, conflicts_with = "terminal_revision")]
+    pub revision: bool,
+    /// Print only the revision last applied in this terminal
+    #[arg(long, conflicts_with = "revision")]
+    pub terminal_revision: bool,
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Args;
 
 #[derive(Args)]
@@ -11,4 +13,7 @@ pub struct ShellSyncCommand {
     /// Skip reconciliation when this terminal already has the current revision
     #[arg(long, conflicts_with = "force")]
     pub if_changed: bool,
+    /// Write the reconciliation script to a file instead of standard output
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
 }
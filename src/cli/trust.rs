@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+#[derive(Args)]
+pub struct TrustCommand {
+    /// Project directory or `.aliasmgr.toml` file; defaults to the current directory
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct UntrustCommand {
+    /// Project directory or `.aliasmgr.toml` file; defaults to the current directory
+    pub path: Option<PathBuf>,
+}
@@ -10,7 +10,11 @@ use env_logger::WriteStyle;
 use owo_colors::{DynColors, Style};
 use serde::Deserialize;
 
+use crate::catalog::layers::{CatalogLayer, SYSTEM_LAYER, USER_LAYER};
+use crate::catalog::project::PROJECT_LAYER;
 use crate::cli::list::ListColumn;
+use crate::core::profile::TagExpression;
+use crate::core::validation::is_valid_tag;
 
 pub const CONFIG_FILE_ENV_VAR: &str = "ALIASMGR_CONFIG_PATH";
 
@@ -46,6 +50,7 @@ pub struct SymbolConfig {
     pub enabled: String,
     pub disabled: String,
     pub global: String,
+    pub suffix: String,
 }
 
 impl Default for SymbolConfig {
@@ -54,6 +59,7 @@ impl Default for SymbolConfig {
             enabled: "✔".into(),
             disabled: "✘".into(),
             global: "⦾".into(),
+            suffix: "⧉".into(),
         }
     }
 }
@@ -93,6 +99,7 @@ pub struct StyleConfig {
     pub enabled: StateStyle,
     pub disabled: StateStyle,
     pub global: StateStyle,
+    pub suffix: StateStyle,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -135,16 +142,31 @@ impl Default for StyleConfig {
                 foreground: "blue".into(),
                 bold: true,
             },
+            suffix: StateStyle {
+                foreground: "magenta".into(),
+                bold: true,
+            },
         }
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CatalogConfig {
+    /// Sort aliases by name whenever the catalog is saved.
+    pub sort: bool,
+    /// Catalogs stacked above the system and user catalogs, in override order.
+    pub layers: Vec<CatalogLayer>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct UserConfig {
     pub color: ColorMode,
     pub symbols: SymbolConfig,
     pub styles: StyleConfig,
     pub list: ListConfig,
+    pub catalog: CatalogConfig,
+    /// Named tag expressions selecting which aliases are active.
+    pub profiles: BTreeMap<String, TagExpression>,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -160,6 +182,25 @@ struct RawConfig {
     symbols: RawSymbolConfig,
     styles: RawStyleConfig,
     list: RawListConfig,
+    catalog: RawCatalogConfig,
+    profiles: BTreeMap<String, String>,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RawCatalogConfig {
+    sort: Option<bool>,
+    layers: Vec<RawLayerConfig>,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawLayerConfig {
+    name: String,
+    path: PathBuf,
     #[serde(flatten)]
     unknown: BTreeMap<String, toml::Value>,
 }
@@ -187,6 +228,7 @@ struct RawSymbolConfig {
     enabled: Option<String>,
     disabled: Option<String>,
     global: Option<String>,
+    suffix: Option<String>,
     #[serde(flatten)]
     unknown: BTreeMap<String, toml::Value>,
 }
@@ -198,6 +240,7 @@ struct RawStyleConfig {
     enabled: Option<RawStateStyle>,
     disabled: Option<RawStateStyle>,
     global: Option<RawStateStyle>,
+    suffix: Option<RawStateStyle>,
     #[serde(flatten)]
     unknown: BTreeMap<String, toml::Value>,
 }
@@ -267,6 +310,7 @@ fn parse_config_with_warnings(content: &str) -> Result<LoadedConfig> {
     collect_unknown_warnings(Some("symbols"), &raw.symbols.unknown, &mut warnings);
     collect_unknown_warnings(Some("styles"), &raw.styles.unknown, &mut warnings);
     collect_unknown_warnings(Some("list"), &raw.list.unknown, &mut warnings);
+    collect_unknown_warnings(Some("catalog"), &raw.catalog.unknown, &mut warnings);
 
     let mut config = UserConfig::default();
     if let Some(mode) = raw.color.mode {
@@ -281,11 +325,15 @@ fn parse_config_with_warnings(content: &str) -> Result<LoadedConfig> {
     if let Some(global) = raw.symbols.global {
         config.symbols.global = global;
     }
+    if let Some(suffix) = raw.symbols.suffix {
+        config.symbols.suffix = suffix;
+    }
 
     for (name, target, style) in [
         ("enabled", &mut config.styles.enabled, raw.styles.enabled),
         ("disabled", &mut config.styles.disabled, raw.styles.disabled),
         ("global", &mut config.styles.global, raw.styles.global),
+        ("suffix", &mut config.styles.suffix, raw.styles.suffix),
     ] {
         apply_style(name, target, style, &mut warnings)?;
     }
@@ -311,6 +359,44 @@ fn parse_config_with_warnings(content: &str) -> Result<LoadedConfig> {
     if let Some(status) = raw.list.status {
         config.list.status = status;
     }
+    if let Some(sort) = raw.catalog.sort {
+        config.catalog.sort = sort;
+    }
+    for layer in raw.catalog.layers {
+        collect_unknown_warnings(Some("catalog.layers"), &layer.unknown, &mut warnings);
+        if !is_valid_tag(&layer.name)
+            || [SYSTEM_LAYER, USER_LAYER, PROJECT_LAYER].contains(&layer.name.as_str())
+        {
+            bail!(
+                "invalid catalog layer name '{}'; names must not be empty, contain whitespace, or be 'system', 'user', or 'project'",
+                layer.name
+            );
+        }
+        if config
+            .catalog
+            .layers
+            .iter()
+            .any(|existing| existing.name == layer.name)
+        {
+            bail!(
+                "catalog layer '{}' is configured more than once",
+                layer.name
+            );
+        }
+        config.catalog.layers.push(CatalogLayer {
+            name: layer.name,
+            path: layer.path,
+        });
+    }
+    for (name, expression) in raw.profiles {
+        if !is_valid_tag(&name) {
+            bail!("invalid profile name '{name}'; names must not be empty or contain whitespace");
+        }
+        let expression = TagExpression::parse(&expression).map_err(|reason| {
+            anyhow::anyhow!("invalid tag expression for profile '{name}': {reason}")
+        })?;
+        config.profiles.insert(name, expression);
+    }
     Ok(LoadedConfig { config, warnings })
 }
 
@@ -344,14 +430,25 @@ fn load_config_from_with_warnings(path: &Path, required: bool) -> Result<LoadedC
 
     let content = fs::read_to_string(path)
         .with_context(|| format!("could not read configuration '{}'", path.display()))?;
-    parse_config_with_warnings(&content)
-        .with_context(|| format!("could not parse configuration '{}'", path.display()))
+    let mut loaded = parse_config_with_warnings(&content)
+        .with_context(|| format!("could not parse configuration '{}'", path.display()))?;
+    // Relative layer paths are relative to the configuration file.
+    let directory = path.parent().unwrap_or(Path::new(""));
+    for layer in &mut loaded.config.catalog.layers {
+        layer.path = directory.join(&layer.path);
+    }
+    Ok(loaded)
 }
 
-pub fn load_config() -> Result<LoadedConfig> {
+/// The configuration file named by the environment, or the default location.
+pub fn configured_config_path() -> PathBuf {
     let explicit = env::var_os(CONFIG_FILE_ENV_VAR).map(PathBuf::from);
-    let path = config_path(explicit.as_deref());
-    load_config_from_with_warnings(&path, explicit.is_some())
+    config_path(explicit.as_deref())
+}
+
+pub fn load_config() -> Result<LoadedConfig> {
+    let explicit = env::var_os(CONFIG_FILE_ENV_VAR).is_some();
+    load_config_from_with_warnings(&configured_config_path(), explicit)
 }
 
 #[cfg(test)]
@@ -372,6 +469,7 @@ mod tests {
         assert!(config.styles.header.bold);
         assert_eq!(config.list.columns, ListColumn::DEFAULTS);
         assert_eq!(config.list.status, StatusColumnMode::Auto);
+        assert!(!config.catalog.sort);
     }
 
     #[test]
@@ -432,6 +530,67 @@ mod tests {
         assert!(parse_config("[list]\nstatus = \"sometimes\"\n").is_err());
     }
 
+    #[test]
+    fn catalog_sorting_is_configurable() {
+        assert!(
+            parse_config("[catalog]\nsort = true\n")
+                .unwrap()
+                .catalog
+                .sort
+        );
+        assert!(parse_config("[catalog]\nsort = \"yes\"\n").is_err());
+    }
+
+    #[test]
+    fn catalog_layers_are_named_and_relative_to_the_configuration() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("config.toml");
+        fs::write(
+            &path,
+            "[[catalog.layers]]\nname = \"work\"\npath = \"work.toml\"\n\n[[catalog.layers]]\nname = \"abs\"\npath = \"/srv/aliases.toml\"\n",
+        )
+        .unwrap();
+        let config = load_config_from(&path, true).unwrap();
+        assert_eq!(
+            config.catalog.layers,
+            [
+                CatalogLayer {
+                    name: "work".into(),
+                    path: directory.path().join("work.toml"),
+                },
+                CatalogLayer {
+                    name: "abs".into(),
+                    path: "/srv/aliases.toml".into(),
+                },
+            ]
+        );
+
+        for name in ["user", "system", "project", "two words"] {
+            let content = format!("[[catalog.layers]]\nname = \"{name}\"\npath = \"a.toml\"\n");
+            assert!(parse_config(&content).is_err(), "{name}");
+        }
+        let duplicate = "[[catalog.layers]]\nname = \"work\"\npath = \"a.toml\"\n[[catalog.layers]]\nname = \"work\"\npath = \"b.toml\"\n";
+        assert!(parse_config(duplicate).is_err());
+    }
+
+    #[test]
+    fn profiles_are_parsed_tag_expressions() {
+        let config =
+            parse_config("[profiles]\nwork = \"work | shell\"\nhome = \"!work\"\n").unwrap();
+        assert_eq!(
+            config.profiles["work"],
+            TagExpression::parse("work | shell").unwrap()
+        );
+        assert_eq!(config.profiles["home"].to_string(), "!work");
+
+        let error = parse_config("[profiles]\nwork = \"work &\"\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid tag expression for profile 'work': expected a tag"
+        );
+        assert!(parse_config("[profiles]\n\"two words\" = \"work\"\n").is_err());
+    }
+
     #[test]
     fn invalid_known_settings_fail() {
         assert!(parse_config("[color]\nmode = \"sometimes\"\n").is_err());
@@ -16,11 +16,12 @@ pub fn add_alias(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::catalog::types::AliasKind;
 
     #[test]
     fn adds_alias_and_rejects_duplicates() {
         let mut catalog = AliasCatalog::new();
-        let alias = Alias::new("ls -la".into(), true, false);
+        let alias = Alias::new("ls -la".into(), true, AliasKind::Regular);
         assert_eq!(
             add_alias(&mut catalog, "ll", &alias),
             Ok(Outcome::CatalogChanged)
@@ -0,0 +1,181 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::catalog::types::{Alias, AliasCatalog};
+use crate::core::conflict::{executable_on_path, is_executable};
+
+/// Facts about this machine that alias conditions are checked against.
+pub struct Host {
+    pub name: String,
+    pub os: String,
+    pub path: Option<OsString>,
+}
+
+impl Host {
+    pub fn current() -> Self {
+        Self {
+            name: current_host_name(),
+            os: env::consts::OS.into(),
+            path: env::var_os("PATH"),
+        }
+    }
+
+    fn is_named(&self, name: &str) -> bool {
+        let short = self.name.split('.').next().unwrap_or_default();
+        self.name.eq_ignore_ascii_case(name) || short.eq_ignore_ascii_case(name)
+    }
+
+    fn has_executable(&self, name: &str) -> bool {
+        executable_location(name, self.path.as_deref()).is_some()
+    }
+}
+
+/// Where a `requires` entry resolves: a name is looked up on `path`, and a
+/// path must itself be executable.
+pub fn executable_location(name: &str, path: Option<&OsStr>) -> Option<PathBuf> {
+    if name.contains('/') {
+        Some(PathBuf::from(name)).filter(|file| is_executable(file))
+    } else {
+        executable_on_path(name, path)
+    }
+}
+
+fn current_host_name() -> String {
+    let from_file = ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .into_iter()
+        .find_map(|path| fs::read_to_string(path).ok());
+    let name = from_file.or_else(|| {
+        Command::new("uname")
+            .arg("-n")
+            .output()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+    });
+    name.map(|name| name.trim().to_owned()).unwrap_or_default()
+}
+
+fn list(values: &BTreeSet<String>) -> String {
+    values.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+/// Why the alias does not apply to this machine, or `None` when every
+/// condition holds.
+pub fn unmet_condition(alias: &Alias, host: &Host) -> Option<String> {
+    let conditions = &alias.conditions;
+    if !conditions.hosts.is_empty() && !conditions.hosts.iter().any(|name| host.is_named(name)) {
+        return Some(format!(
+            "host '{}' is not one of {}",
+            host.name,
+            list(&conditions.hosts)
+        ));
+    }
+    if !conditions.os.is_empty() && !conditions.os.contains(&host.os) {
+        return Some(format!(
+            "OS '{}' is not one of {}",
+            host.os,
+            list(&conditions.os)
+        ));
+    }
+    let missing = conditions
+        .requires
+        .iter()
+        .filter(|name| !host.has_executable(name))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    (!missing.is_empty()).then(|| format!("{} not found on PATH", list(&missing)))
+}
+
+/// Record the aliases whose conditions do not hold on this machine as inactive.
+pub fn mark_unmet_conditions(catalog: &mut AliasCatalog, host: &Host) {
+    for (name, alias) in &catalog.aliases {
+        if let Some(reason) = unmet_condition(alias, host) {
+            catalog.inactive.insert(name.clone(), reason);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::types::AliasKind;
+    use std::path::Path;
+
+    fn host(path: Option<&Path>) -> Host {
+        Host {
+            name: "build-01.example.com".into(),
+            os: "linux".into(),
+            path: path.map(|path| path.as_os_str().to_owned()),
+        }
+    }
+
+    fn alias(hosts: &[&str], os: &[&str], requires: &[&str]) -> Alias {
+        let mut alias = Alias::new("cmd".into(), true, AliasKind::Regular);
+        let set = |values: &[&str]| values.iter().map(|value| (*value).to_owned()).collect();
+        alias.conditions.hosts = set(hosts);
+        alias.conditions.os = set(os);
+        alias.conditions.requires = set(requires);
+        alias
+    }
+
+    #[test]
+    fn hosts_match_with_or_without_the_domain() {
+        let host = host(None);
+        assert_eq!(
+            unmet_condition(&alias(&["BUILD-01"], &[], &[]), &host),
+            None
+        );
+        assert_eq!(
+            unmet_condition(&alias(&["build-01.example.com"], &[], &[]), &host),
+            None
+        );
+        assert_eq!(
+            unmet_condition(&alias(&["laptop", "desk"], &[], &[]), &host).unwrap(),
+            "host 'build-01.example.com' is not one of desk, laptop"
+        );
+    }
+
+    #[test]
+    fn os_and_executables_are_checked() {
+        let directory = tempfile::tempdir().unwrap();
+        let executable = directory.path().join("xdg-open");
+        fs::write(&executable, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let host = host(Some(directory.path()));
+
+        assert_eq!(
+            unmet_condition(&alias(&[], &["linux"], &["xdg-open"]), &host),
+            None
+        );
+        assert_eq!(
+            unmet_condition(&alias(&[], &["macos"], &[]), &host).unwrap(),
+            "OS 'linux' is not one of macos"
+        );
+        assert_eq!(
+            unmet_condition(&alias(&[], &[], &["open", "pbcopy", "xdg-open"]), &host).unwrap(),
+            "open, pbcopy not found on PATH"
+        );
+        assert_eq!(
+            unmet_condition(&alias(&[], &[], &[executable.to_str().unwrap()]), &host),
+            None
+        );
+
+        let mut catalog = AliasCatalog::new();
+        catalog
+            .aliases
+            .insert("o".into(), alias(&[], &["macos"], &[]));
+        catalog
+            .aliases
+            .insert("x".into(), alias(&[], &["linux"], &[]));
+        mark_unmet_conditions(&mut catalog, &host);
+        assert_eq!(catalog.inactive.keys().collect::<Vec<_>>(), ["o"]);
+    }
+}
@@ -25,6 +25,26 @@ for name do
 done
 "#;
 
+const FISH_BUILTIN_QUERY: &str = r#"
+set -l builtins (builtin -n)
+for name in $argv
+    if contains -- $name $builtins
+        printf '%s\0' $name
+    end
+end
+"#;
+
+const POSIX_BUILTIN_QUERY: &str = r#"
+for name do
+    case "$(command -V "$name" 2>/dev/null)" in
+        *"shell builtin"*) printf '%s\0' "$name" ;;
+    esac
+done
+"#;
+
+const NU_BUILTIN_QUERY: &str =
+    "help commands | where command_type in [built-in keyword] | get name | str join (char nul)";
+
 fn shell_builtin_names(names: &[String], shell: &ShellType) -> HashSet<String> {
     let (program, options, query) = match shell {
         ShellType::Bash => (
@@ -33,15 +53,25 @@ fn shell_builtin_names(names: &[String], shell: &ShellType) -> HashSet<String> {
             BASH_BUILTIN_QUERY,
         ),
         ShellType::Zsh => ("zsh", ["-dfc"].as_slice(), ZSH_BUILTIN_QUERY),
+        ShellType::Fish => ("fish", ["--no-config", "-c"].as_slice(), FISH_BUILTIN_QUERY),
+        ShellType::Nu => (
+            "nu",
+            ["--no-config-file", "-c"].as_slice(),
+            NU_BUILTIN_QUERY,
+        ),
+        ShellType::Posix => ("sh", ["-c"].as_slice(), POSIX_BUILTIN_QUERY),
     };
 
-    let output = Command::new(program)
-        .args(options)
-        .arg(query)
-        .arg("aliasmgr")
-        .args(names)
-        .env_remove("BASH_ENV")
-        .output();
+    let mut command = Command::new(program);
+    command.args(options).arg(query);
+    match shell {
+        // POSIX-style shells bind the first argument after the script to `$0`.
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => command.arg("aliasmgr").args(names),
+        ShellType::Fish => command.args(names),
+        // Nushell scripts do not receive arguments, so every builtin is listed and filtered here.
+        ShellType::Nu => &mut command,
+    };
+    let output = command.env_remove("BASH_ENV").env_remove("ENV").output();
 
     let Ok(output) = output else {
         debug!("Could not query {shell} builtins; continuing with PATH checks.");
@@ -58,21 +88,22 @@ fn shell_builtin_names(names: &[String], shell: &ShellType) -> HashSet<String> {
         .split(|byte| *byte == 0)
         .filter(|name| !name.is_empty())
         .filter_map(|name| String::from_utf8(name.to_vec()).ok())
+        .filter(|name| names.contains(name))
         .collect()
 }
 
 #[cfg(unix)]
-fn is_executable(path: &Path) -> bool {
+pub(crate) fn is_executable(path: &Path) -> bool {
     path.metadata()
         .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
 }
 
 #[cfg(not(unix))]
-fn is_executable(path: &Path) -> bool {
+pub(crate) fn is_executable(path: &Path) -> bool {
     path.is_file()
 }
 
-fn executable_on_path(name: &str, path: Option<&OsStr>) -> Option<PathBuf> {
+pub(crate) fn executable_on_path(name: &str, path: Option<&OsStr>) -> Option<PathBuf> {
     if name.contains('/') {
         return None;
     }
@@ -194,5 +225,10 @@ mod tests {
         assert!(ZSH_BUILTIN_QUERY.contains("builtin whence -w -- \"$name\""));
         assert!(BASH_BUILTIN_QUERY.contains("for name do"));
         assert!(ZSH_BUILTIN_QUERY.contains("for name do"));
+        assert!(FISH_BUILTIN_QUERY.contains("builtin -n"));
+        assert!(FISH_BUILTIN_QUERY.contains("for name in $argv"));
+        assert!(NU_BUILTIN_QUERY.contains("built-in"));
+        assert!(POSIX_BUILTIN_QUERY.contains("command -V \"$name\""));
+        assert!(POSIX_BUILTIN_QUERY.contains("for name do"));
     }
 }
@@ -0,0 +1,51 @@
+/// Compare two texts line by line, prefixing removed lines with `-`, added
+/// lines with `+`, and unchanged lines with a space.
+pub fn line_diff(before: &str, after: &str) -> Vec<String> {
+    let before = before.lines().collect::<Vec<_>>();
+    let after = after.lines().collect::<Vec<_>>();
+
+    // common[i][j] is the longest common subsequence of before[i..] and after[j..].
+    let mut common = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            lines.push(format!(" {}", before[i]));
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("-{}", before[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+{}", after[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_removed_added_and_unchanged_lines() {
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nc\nd\n"),
+            [" a", "-b", " c", "+d"]
+        );
+        assert_eq!(line_diff("", "x\n"), ["+x"]);
+        assert!(line_diff("", "").is_empty());
+    }
+}
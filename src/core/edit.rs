@@ -20,11 +20,12 @@ pub fn edit_alias(
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
     use super::*;
+    use crate::catalog::types::AliasKind;
 
     #[test]
     fn missing_unchanged_and_changed_aliases_have_distinct_outcomes() {
         let mut catalog = AliasCatalog::new();
-        let original = Alias::new("old".into(), true, false);
+        let original = Alias::new("old".into(), true, AliasKind::Regular);
         assert_eq!(
             edit_alias(&mut catalog, "missing", &original),
             Err(Failure::AliasDoesNotExist)
@@ -34,7 +35,7 @@ mod tests {
             edit_alias(&mut catalog, "name", &original),
             Ok(Outcome::NoChanges)
         );
-        let changed = Alias::new("new".into(), true, false);
+        let changed = Alias::new("new".into(), true, AliasKind::Regular);
         assert_eq!(
             edit_alias(&mut catalog, "name", &changed),
             Ok(Outcome::CatalogChanged)
@@ -0,0 +1,95 @@
+use super::validation::is_valid_variable_name;
+use super::{Failure, Outcome};
+use crate::catalog::types::{AliasCatalog, Variable};
+
+/// Add or replace a variable.
+pub fn set_variable(
+    catalog: &mut AliasCatalog,
+    name: &str,
+    variable: Variable,
+) -> Result<Outcome, Failure> {
+    if !is_valid_variable_name(name) {
+        return Err(Failure::InvalidVariableName);
+    }
+    if catalog.variables.get(name) == Some(&variable) {
+        return Ok(Outcome::NoChanges);
+    }
+    catalog.variables.insert(name.into(), variable);
+    Ok(Outcome::CatalogChanged)
+}
+
+pub fn remove_variable(catalog: &mut AliasCatalog, name: &str) -> Result<Outcome, Failure> {
+    catalog
+        .variables
+        .remove(name)
+        .map(|_| Outcome::CatalogChanged)
+        .ok_or(Failure::VariableDoesNotExist)
+}
+
+pub fn set_variable_enabled(
+    catalog: &mut AliasCatalog,
+    name: &str,
+    enabled: bool,
+) -> Result<Outcome, Failure> {
+    let variable = catalog
+        .variables
+        .get_mut(name)
+        .ok_or(Failure::VariableDoesNotExist)?;
+    if variable.enabled == enabled {
+        return Ok(Outcome::NoChanges);
+    }
+    variable.enabled = enabled;
+    Ok(Outcome::CatalogChanged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_are_set_toggled_and_removed() {
+        let mut catalog = AliasCatalog::new();
+        let editor = Variable::new("nvim".into(), true);
+        assert_eq!(
+            set_variable(&mut catalog, "EDITOR", editor.clone()),
+            Ok(Outcome::CatalogChanged)
+        );
+        assert_eq!(
+            set_variable(&mut catalog, "EDITOR", editor),
+            Ok(Outcome::NoChanges)
+        );
+        assert_eq!(
+            set_variable(&mut catalog, "MY-VAR", Variable::new("x".into(), true)),
+            Err(Failure::InvalidVariableName)
+        );
+        for reserved in ["ALIASMGR_MANAGED_ALIASES", "ALIASMGR_PROFILE"] {
+            assert_eq!(
+                set_variable(&mut catalog, reserved, Variable::new("x".into(), true)),
+                Err(Failure::InvalidVariableName)
+            );
+        }
+
+        assert_eq!(
+            set_variable_enabled(&mut catalog, "EDITOR", false),
+            Ok(Outcome::CatalogChanged)
+        );
+        assert!(!catalog.variables["EDITOR"].enabled);
+        assert_eq!(
+            set_variable_enabled(&mut catalog, "EDITOR", false),
+            Ok(Outcome::NoChanges)
+        );
+        assert_eq!(
+            set_variable_enabled(&mut catalog, "PAGER", true),
+            Err(Failure::VariableDoesNotExist)
+        );
+
+        assert_eq!(
+            remove_variable(&mut catalog, "EDITOR"),
+            Ok(Outcome::CatalogChanged)
+        );
+        assert_eq!(
+            remove_variable(&mut catalog, "EDITOR"),
+            Err(Failure::VariableDoesNotExist)
+        );
+    }
+}
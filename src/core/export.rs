@@ -0,0 +1,117 @@
+use crate::app::shell::ShellType;
+use crate::catalog::types::{Alias, Variable};
+use crate::core::sync::{alias_definition, export_command, is_definable};
+use crate::core::validation::is_valid_variable_name;
+
+/// A standalone alias script and the aliases and variables it could not
+/// define.
+pub struct ShellExport {
+    pub script: String,
+    pub skipped: Vec<String>,
+    pub skipped_variables: Vec<String>,
+}
+
+fn comment(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(|line| format!("# {line}").trim_end().to_owned())
+}
+
+/// Render aliases and variables as a script that `shell` can source without
+/// aliasmgr.
+///
+/// Disabled aliases and variables are kept as commented-out definitions, and
+/// descriptions are optionally written as comments above their definition.
+pub fn export_script(
+    aliases: &[(&str, &Alias)],
+    variables: &[(&str, &Variable)],
+    shell: &ShellType,
+    descriptions: bool,
+) -> ShellExport {
+    let mut lines = vec![format!("# Aliases exported by aliasmgr for {shell}")];
+    let mut skipped = Vec::new();
+    for (name, alias) in aliases {
+        if !is_definable(name, alias, shell) {
+            skipped.push((*name).to_owned());
+            continue;
+        }
+        if descriptions && let Some(description) = &alias.description {
+            lines.extend(comment(description));
+        }
+        let definition = alias_definition(name, alias, shell);
+        if alias.enabled {
+            lines.push(definition);
+        } else {
+            lines.extend(comment(&definition));
+        }
+    }
+    let mut skipped_variables = Vec::new();
+    for (name, variable) in variables {
+        if !is_valid_variable_name(name) {
+            skipped_variables.push((*name).to_owned());
+            continue;
+        }
+        if descriptions && let Some(description) = &variable.description {
+            lines.extend(comment(description));
+        }
+        let definition = export_command(name, &variable.value, shell);
+        if variable.enabled {
+            lines.push(definition);
+        } else {
+            lines.extend(comment(&definition));
+        }
+    }
+    ShellExport {
+        script: lines.join("\n") + "\n",
+        skipped,
+        skipped_variables,
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::types::AliasKind;
+
+    #[test]
+    fn scripts_comment_out_disabled_aliases_and_skip_unsupported_ones() {
+        let mut documented = Alias::new("ls -la".into(), true, AliasKind::Regular);
+        documented.description = Some("List files".into());
+        let disabled = Alias::new("git status\necho done".into(), false, AliasKind::Regular);
+        let global = Alias::new("| grep".into(), true, AliasKind::Global);
+        let aliases = [("ll", &documented), ("gs", &disabled), ("G", &global)];
+
+        let export = export_script(&aliases, &[], &ShellType::Bash, true);
+        assert_eq!(
+            export.script,
+            "# Aliases exported by aliasmgr for BASH\n# List files\nalias -- 'll=ls -la'\n# alias -- 'gs=git status\n# echo done'\n"
+        );
+        assert_eq!(export.skipped, ["G"]);
+
+        let export = export_script(&aliases, &[], &ShellType::Fish, false);
+        assert!(export.script.contains("\nalias -- 'll' 'ls -la'\n"));
+        assert!(!export.script.contains("List files"));
+    }
+
+    #[test]
+    fn scripts_export_variables_after_aliases() {
+        let alias = Alias::new("ls -la".into(), true, AliasKind::Regular);
+        let mut editor = Variable::new("nvim".into(), true);
+        editor.description = Some("Editor".into());
+        let pager = Variable::new("less -R".into(), false);
+        let invalid = Variable::new("x".into(), true);
+        let variables = [("EDITOR", &editor), ("PAGER", &pager), ("MY-VAR", &invalid)];
+
+        let export = export_script(&[("ll", &alias)], &variables, &ShellType::Bash, true);
+        assert_eq!(
+            export.script,
+            "# Aliases exported by aliasmgr for BASH\nalias -- 'll=ls -la'\n# Editor\nexport EDITOR='nvim'\n# export PAGER='less -R'\n"
+        );
+        assert_eq!(export.skipped_variables, ["MY-VAR"]);
+
+        let fish = export_script(&[], &variables, &ShellType::Fish, false);
+        assert!(fish.script.contains("\nset -gx EDITOR 'nvim'\n"));
+        let nu = export_script(&[], &variables, &ShellType::Nu, false);
+        assert!(nu.script.contains("\n$env.EDITOR = \"nvim\"\n"));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::catalog::types::{Alias, AliasCatalog, AliasKind, Variable};
+
+fn tag_list(alias: &Alias) -> String {
+    alias.tags.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+fn field_changes(before: &Alias, after: &Alias) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.command != after.command {
+        changes.push(format!(
+            "command '{}' -> '{}'",
+            before.command, after.command
+        ));
+    }
+    if before.shell_commands != after.shell_commands {
+        changes.push("shell commands changed".into());
+    }
+    if before.enabled != after.enabled {
+        changes.push(if after.enabled { "enabled" } else { "disabled" }.into());
+    }
+    if before.kind != after.kind {
+        changes.push(
+            match (before.kind, after.kind) {
+                (AliasKind::Global, AliasKind::Regular) => "not global",
+                (AliasKind::Suffix, AliasKind::Regular) => "not suffix",
+                (_, kind) => kind.as_str(),
+            }
+            .into(),
+        );
+    }
+    if before.function != after.function {
+        changes.push(
+            if after.function {
+                "function"
+            } else {
+                "not function"
+            }
+            .into(),
+        );
+    }
+    if before.tags != after.tags {
+        changes.push(format!(
+            "tags [{}] -> [{}]",
+            tag_list(before),
+            tag_list(after)
+        ));
+    }
+    if before.description != after.description {
+        changes.push(match &after.description {
+            Some(description) => format!("description '{description}'"),
+            None => "description cleared".into(),
+        });
+    }
+    changes
+}
+
+fn variable_changes(before: &Variable, after: &Variable) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.value != after.value {
+        changes.push(format!("value '{}' -> '{}'", before.value, after.value));
+    }
+    if before.enabled != after.enabled {
+        changes.push(if after.enabled { "enabled" } else { "disabled" }.into());
+    }
+    if before.tags != after.tags || before.description != after.description {
+        changes.push("metadata changed".into());
+    }
+    changes
+}
+
+/// Describe how aliases differ between two catalogs, one line per alias,
+/// followed by the changed variables as `$NAME`.
+pub fn describe_changes(before: &AliasCatalog, after: &AliasCatalog) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (name, alias) in &before.aliases {
+        match after.aliases.get(name) {
+            None => lines.push(format!("- {name}: {}", alias.command)),
+            Some(updated) => {
+                let changes = field_changes(alias, updated);
+                if !changes.is_empty() {
+                    lines.push(format!("~ {name}: {}", changes.join(", ")));
+                }
+            }
+        }
+    }
+    for (name, alias) in &after.aliases {
+        if !before.aliases.contains_key(name) {
+            lines.push(format!("+ {name}: {}", alias.command));
+        }
+    }
+    for (name, variable) in &before.variables {
+        match after.variables.get(name) {
+            None => lines.push(format!("- ${name}: {}", variable.value)),
+            Some(updated) => {
+                let changes = variable_changes(variable, updated);
+                if !changes.is_empty() {
+                    lines.push(format!("~ ${name}: {}", changes.join(", ")));
+                }
+            }
+        }
+    }
+    for (name, variable) in &after.variables {
+        if !before.variables.contains_key(name) {
+            lines.push(format!("+ ${name}: {}", variable.value));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_list_removed_changed_and_added_aliases() {
+        let mut before = AliasCatalog::new();
+        before.aliases.insert(
+            "gone".into(),
+            Alias::new("echo gone".into(), true, AliasKind::Regular),
+        );
+        before.aliases.insert(
+            "ll".into(),
+            Alias::new("ls".into(), true, AliasKind::Regular),
+        );
+        before.aliases.insert(
+            "same".into(),
+            Alias::new("echo same".into(), true, AliasKind::Regular),
+        );
+
+        let mut after = before.clone();
+        after.aliases.remove("gone");
+        let ll = after.aliases.get_mut("ll").unwrap();
+        ll.command = "ls -la".into();
+        ll.enabled = false;
+        ll.tags.insert("files".into());
+        after.aliases.insert(
+            "new".into(),
+            Alias::new("echo new".into(), true, AliasKind::Regular),
+        );
+
+        assert_eq!(
+            describe_changes(&before, &after),
+            [
+                "- gone: echo gone",
+                "~ ll: command 'ls' -> 'ls -la', disabled, tags [] -> [files]",
+                "+ new: echo new",
+            ]
+        );
+        assert!(describe_changes(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn changes_list_variables_after_aliases() {
+        let mut before = AliasCatalog::new();
+        before
+            .variables
+            .insert("EDITOR".into(), Variable::new("vi".into(), true));
+        before
+            .variables
+            .insert("PAGER".into(), Variable::new("less".into(), true));
+
+        let mut after = before.clone();
+        after.variables.remove("PAGER");
+        after.variables.get_mut("EDITOR").unwrap().value = "nvim".into();
+        after.aliases.insert(
+            "ll".into(),
+            Alias::new("ls -la".into(), true, AliasKind::Regular),
+        );
+        after
+            .variables
+            .insert("VISUAL".into(), Variable::new("code".into(), false));
+
+        assert_eq!(
+            describe_changes(&before, &after),
+            [
+                "+ ll: ls -la",
+                "~ $EDITOR: value 'vi' -> 'nvim'",
+                "- $PAGER: less",
+                "+ $VISUAL: code",
+            ]
+        );
+    }
+}
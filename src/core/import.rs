@@ -1,142 +1,424 @@
-//! Parse individual Bash and Zsh alias declarations.
+//! Parse individual Bash and Zsh alias declarations, including the output of
+//! Bash `alias -p` and Zsh `alias -L`, and the JSON written by `list` and
+//! `export`.
 
-use crate::catalog::types::Alias;
-use crate::core::validation::is_valid_alias_name;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+
+use crate::catalog::spec::resolve_alias_kind;
+use crate::catalog::types::{Alias, AliasConditions, AliasKind, SHELL_COMMAND_KEYS};
+use crate::core::validation::{is_valid_alias_name, is_valid_tag};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ParsedLine {
     Ignored,
-    Unsupported,
+    /// A standalone comment, without its leading `#`.
+    Comment(String),
+    /// A line that cannot be imported, with the reason.
+    Unsupported(String),
     Alias {
         name: String,
         command: String,
-        global: bool,
+        kind: AliasKind,
+        /// Text of a trailing `# comment`, if any.
+        comment: Option<String>,
     },
 }
 
 pub fn parse_alias_line(line: &str) -> ParsedLine {
     let line = line.trim();
-    if line.is_empty() || line.starts_with('#') {
+    if line.is_empty() {
         return ParsedLine::Ignored;
     }
+    if let Some(comment) = line.strip_prefix('#') {
+        return ParsedLine::Comment(comment.trim().into());
+    }
 
-    let Some(mut declaration) = line.strip_prefix("alias") else {
-        return ParsedLine::Unsupported;
+    let declaration = line
+        .strip_prefix("alias")
+        .filter(|declaration| declaration.starts_with(char::is_whitespace));
+    let Some(mut declaration) = declaration.map(str::trim_start) else {
+        return unsupported("not an alias declaration");
     };
-    if !declaration.starts_with(char::is_whitespace) {
-        return ParsedLine::Unsupported;
-    }
-    declaration = declaration.trim_start();
 
-    let global = if let Some(rest) = declaration.strip_prefix("-g") {
-        if !rest.starts_with(char::is_whitespace) {
-            return ParsedLine::Unsupported;
+    // Zsh `alias -L` marks global aliases with `-g` and suffix aliases with
+    // `-s`; `--` precedes names like `-`.
+    let mut kind = AliasKind::Regular;
+    while declaration.starts_with('-') {
+        let (flag, rest) = declaration
+            .split_once(char::is_whitespace)
+            .unwrap_or((declaration, ""));
+        let flag_kind = match flag {
+            "-g" => AliasKind::Global,
+            "-s" => AliasKind::Suffix,
+            "--" => {
+                declaration = rest.trim_start();
+                break;
+            }
+            _ => return unsupported(format!("unsupported alias option '{flag}'")),
+        };
+        if kind != AliasKind::Regular && kind != flag_kind {
+            return unsupported("alias cannot be both global and suffix");
         }
+        kind = flag_kind;
         declaration = rest.trim_start();
-        true
-    } else {
-        false
-    };
+    }
 
     let Some((name, value)) = declaration.split_once('=') else {
-        return ParsedLine::Unsupported;
+        return unsupported("alias has no '=' and value");
     };
     if !is_valid_alias_name(name) {
-        return ParsedLine::Unsupported;
+        return unsupported(format!("invalid alias name '{name}'"));
     }
 
-    let Some((command, remainder)) = parse_shell_word(value) else {
-        return ParsedLine::Unsupported;
+    let (command, remainder) = match parse_shell_word(value) {
+        Ok(word) => word,
+        Err(reason) => return ParsedLine::Unsupported(reason),
     };
     let remainder = remainder.trim_start();
-    if !remainder.is_empty() && !remainder.starts_with('#') {
-        return ParsedLine::Unsupported;
-    }
+    let comment = if remainder.is_empty() {
+        None
+    } else if let Some(comment) = remainder.strip_prefix('#') {
+        Some(comment.trim()).filter(|comment| !comment.is_empty())
+    } else {
+        return unsupported(format!(
+            "unexpected text after the alias value: '{remainder}'"
+        ));
+    };
 
     ParsedLine::Alias {
         name: name.into(),
         command,
-        global,
+        kind,
+        comment: comment.map(Into::into),
     }
 }
 
-pub fn is_identical(existing: &Alias, command: &str, global: bool) -> bool {
-    existing.command == command && existing.global == global
+/// Split shell declarations into logical lines, each with the index of its
+/// first line, joining lines while a quoted value is still open so that
+/// multi-line alias values from `alias -p` and `alias -L` stay together.
+pub fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut logical = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (index, line) in content.lines().enumerate() {
+        let (start, text) = match pending.take() {
+            Some((start, mut text)) => {
+                text.push('\n');
+                text.push_str(line);
+                (start, text)
+            }
+            None => (index, line.to_owned()),
+        };
+        if has_open_quote(&text) {
+            pending = Some((start, text));
+        } else {
+            logical.push((start, text));
+        }
+    }
+    logical.extend(pending);
+    logical
 }
 
-fn parse_shell_word(input: &str) -> Option<(String, &str)> {
-    #[derive(Clone, Copy)]
-    enum Quote {
-        Single,
-        Double,
+/// Whether a quote in `text` is still open at its end. Quotes inside
+/// comments are ignored.
+fn has_open_quote(text: &str) -> bool {
+    let mut quote = None;
+    let mut word_start = true;
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        match (quote, character) {
+            (None | Some('"'), '\\') => {
+                chars.next();
+            }
+            (None, '\'' | '"') => quote = Some(character),
+            (None, '#') if word_start => {
+                chars.by_ref().find(|character| *character == '\n');
+                word_start = true;
+                continue;
+            }
+            (Some(active), character) if character == active => quote = None,
+            _ => {}
+        }
+        word_start = quote.is_none() && character.is_whitespace();
     }
+    quote.is_some()
+}
 
+fn unsupported(reason: impl Into<String>) -> ParsedLine {
+    ParsedLine::Unsupported(reason.into())
+}
+
+fn is_header_decoration(character: char) -> bool {
+    matches!(character, '#' | '-' | '=' | '*' | '~')
+}
+
+/// Turn a section header comment such as `# --- Git shortcuts ---` or
+/// `## Git shortcuts` into a tag like `git-shortcuts`, or `None` for other
+/// comments, such as prose and commented-out code.
+///
+/// Tags keep only lowercase letters, digits, `_`, and `-`, so that profile
+/// expressions can name them.
+pub fn section_tag(comment: &str) -> Option<String> {
+    let is_header = comment.starts_with('#')
+        || comment
+            .chars()
+            .take_while(|character| is_header_decoration(*character))
+            .count()
+            >= 2;
+    if !is_header {
+        return None;
+    }
+    let title = comment.trim_matches(|character: char| {
+        character.is_whitespace() || is_header_decoration(character)
+    });
+    if matches!(parse_alias_line(title), ParsedLine::Alias { .. }) {
+        return None;
+    }
+    let mut tag = String::new();
+    for character in title.to_lowercase().chars() {
+        if character.is_ascii_alphanumeric() || character == '_' {
+            tag.push(character);
+        } else if (character == '-' || character.is_whitespace()) && !tag.ends_with('-') {
+            tag.push('-');
+        }
+    }
+    let tag = tag.trim_matches('-');
+    (!tag.is_empty()).then(|| tag.to_owned())
+}
+
+pub fn is_identical(existing: &Alias, command: &str, kind: AliasKind) -> bool {
+    existing.command == command && existing.kind == kind
+}
+
+/// Compare every field a JSON record carries, so edited metadata is imported.
+pub fn is_same_alias(existing: &Alias, incoming: &Alias) -> bool {
+    is_identical(existing, &incoming.command, incoming.kind)
+        && existing.shell_commands == incoming.shell_commands
+        && existing.function == incoming.function
+        && existing.enabled == incoming.enabled
+        && existing.description == incoming.description
+        && existing.tags == incoming.tags
+        && existing.conditions == incoming.conditions
+}
+
+/// Combine an imported alias with the catalog's alias of the same name. The
+/// imported commands and kind win, while the catalog keeps its enabled
+/// state, description, and conditions, and both sets of tags are kept.
+pub fn merge_alias(existing: &Alias, incoming: &Alias) -> Alias {
+    let mut merged = existing.clone();
+    merged.command.clone_from(&incoming.command);
+    merged.shell_commands.clone_from(&incoming.shell_commands);
+    merged.kind = incoming.kind;
+    merged.function = incoming.function;
+    if merged.description.is_none() {
+        merged.description.clone_from(&incoming.description);
+    }
+    merged.tags.extend(incoming.tags.iter().cloned());
+    if merged.conditions.is_empty() {
+        merged.conditions.clone_from(&incoming.conditions);
+    }
+    merged.refresh_representation();
+    merged
+}
+
+/// Lay out the catalog's alias next to an imported one, marking the fields
+/// that differ with `*`.
+pub fn side_by_side(existing: &Alias, incoming: &Alias) -> Vec<String> {
+    fn fields(alias: &Alias) -> [String; 5] {
+        [
+            alias.command.replace('\n', "\\n"),
+            alias.enabled.to_string(),
+            alias.kind.to_string(),
+            alias.tags.iter().cloned().collect::<Vec<_>>().join(", "),
+            alias.description.clone().unwrap_or_default(),
+        ]
+    }
+    let labels = ["command", "enabled", "kind", "tags", "description"];
+    let (existing, incoming) = (fields(existing), fields(incoming));
+    let width = existing
+        .iter()
+        .map(|value| UnicodeWidthStr::width(value.as_str()))
+        .chain([UnicodeWidthStr::width("catalog")])
+        .max()
+        .unwrap_or_default();
+    let row = |marker: char, label: &str, left: &str, right: &str| {
+        let padding = " ".repeat(width - UnicodeWidthStr::width(left));
+        format!("{marker} {label:<11} {left}{padding} | {right}")
+            .trim_end()
+            .to_owned()
+    };
+
+    let mut lines = vec![row(' ', "", "catalog", "imported")];
+    for ((label, left), right) in labels.iter().zip(&existing).zip(&incoming) {
+        let marker = if left == right { ' ' } else { '*' };
+        lines.push(row(marker, label, left, right));
+    }
+    lines
+}
+
+/// A named alias read from an import source, or the reason it was skipped.
+pub type ImportRecord = Result<(String, Alias), String>;
+
+/// An alias as written by `list --format json` and `export --format json`.
+#[derive(Deserialize)]
+struct JsonAlias {
+    name: String,
+    command: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+    #[serde(default)]
+    global: bool,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    function: bool,
+    #[serde(default)]
+    tags: BTreeSet<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    commands: BTreeMap<String, String>,
+    #[serde(default)]
+    hosts: BTreeSet<String>,
+    #[serde(default)]
+    os: BTreeSet<String>,
+    #[serde(default)]
+    requires: BTreeSet<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Whether import content is a JSON alias list rather than shell declarations.
+pub fn looks_like_json(content: &str) -> bool {
+    content.trim_start().starts_with('[')
+}
+
+/// Parse a JSON alias list into each record's alias, or the reason the record
+/// cannot be imported.
+pub fn parse_json_aliases(content: &str) -> Result<Vec<ImportRecord>, serde_json::Error> {
+    let records = serde_json::from_str::<Vec<serde_json::Value>>(content)?;
+    Ok(records.into_iter().map(parse_json_alias).collect())
+}
+
+fn parse_json_alias(record: serde_json::Value) -> ImportRecord {
+    let record = serde_json::from_value::<JsonAlias>(record).map_err(|error| error.to_string())?;
+    if !is_valid_alias_name(&record.name) {
+        return Err(format!("invalid alias name '{}'", record.name));
+    }
+    if let Some(tag) = record.tags.iter().find(|tag| !is_valid_tag(tag)) {
+        return Err(format!("invalid tag '{tag}'"));
+    }
+    if let Some(shell) = record
+        .commands
+        .keys()
+        .find(|shell| !SHELL_COMMAND_KEYS.contains(&shell.as_str()))
+    {
+        return Err(format!("unknown shell '{shell}' in commands"));
+    }
+    let kind = resolve_alias_kind(record.kind.as_deref(), record.global)?;
+    if record.function && kind != AliasKind::Regular {
+        return Err(format!("functions cannot be {kind} aliases"));
+    }
+    let mut alias = Alias::new(record.command, record.enabled, kind);
+    alias.function = record.function;
+    alias.shell_commands = record.commands;
+    alias.description = record.description;
+    alias.tags = record.tags;
+    alias.conditions = AliasConditions {
+        hosts: record.hosts,
+        os: record.os,
+        requires: record.requires,
+    };
+    alias.refresh_representation();
+    Ok((record.name, alias))
+}
+
+/// Read the alias value the way the defining shell would, returning the text
+/// the alias expands to and the rest of the line.
+///
+/// The shell expands `$` and backticks while defining the alias, so the value
+/// it stored cannot be recovered from the declaration; those are rejected
+/// unless single-quoted or escaped. Inside double quotes, `~`, globs, and
+/// control operators are inert and kept literally. Unquoted globs and control
+/// operators are rejected too, since the shell would expand them or treat them
+/// as a new command.
+fn parse_shell_word(input: &str) -> Result<(String, &str), String> {
     let mut command = String::new();
     let mut chars = input.char_indices().peekable();
-    let mut quote = None;
-    let mut consumed = 0;
+    let mut end = input.len();
     let mut saw_content = false;
 
     while let Some((index, character)) = chars.next() {
-        consumed = index + character.len_utf8();
-        match quote {
-            Some(Quote::Single) => {
-                if character == '\'' {
-                    quote = None;
-                } else {
-                    command.push(character);
-                }
-                saw_content = true;
-            }
-            Some(Quote::Double) => {
-                if character == '"' {
-                    quote = None;
-                    saw_content = true;
-                } else if matches!(character, '$' | '`') {
-                    return None;
-                } else if character == '\\' {
-                    let (_, escaped) = chars.next()?;
-                    consumed += escaped.len_utf8();
-                    command.push(escaped);
-                    saw_content = true;
-                } else {
-                    command.push(character);
-                    saw_content = true;
-                }
+        match character {
+            character if character.is_whitespace() => {
+                end = index;
+                break;
             }
-            None => match character {
-                '\'' | '"' => {
-                    quote = Some(if character == '\'' {
-                        Quote::Single
-                    } else {
-                        Quote::Double
-                    });
-                    saw_content = true;
+            '\'' => loop {
+                match chars.next() {
+                    Some((_, '\'')) => break,
+                    Some((_, character)) => command.push(character),
+                    None => return Err("unterminated single quote".into()),
                 }
-                '\\' => {
-                    let (_, escaped) = chars.next()?;
-                    consumed += escaped.len_utf8();
-                    command.push(escaped);
-                    saw_content = true;
-                }
-                character if character.is_whitespace() => {
-                    consumed = index;
-                    break;
-                }
-                '$' | '`' | '*' | '?' | '[' | '{' | '~' | ';' | '&' | '|' | '<' | '>' | '('
-                | ')' => return None,
-                _ => {
-                    command.push(character);
-                    saw_content = true;
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped @ ('$' | '`' | '"' | '\\'))) => command.push(escaped),
+                        Some((_, other)) => {
+                            command.push('\\');
+                            command.push(other);
+                        }
+                        None => return Err("unterminated double quote".into()),
+                    },
+                    Some((_, '$'))
+                        if chars
+                            .peek()
+                            .is_some_and(|(_, next)| !next.is_whitespace() && *next != '"') =>
+                    {
+                        return Err(expanded_when_defined('$'));
+                    }
+                    Some((_, '`')) => return Err(expanded_when_defined('`')),
+                    Some((_, character)) => command.push(character),
+                    None => return Err("unterminated double quote".into()),
                 }
             },
+            '\\' => match chars.next() {
+                Some((_, escaped)) => command.push(escaped),
+                None => return Err("line continuations are not supported".into()),
+            },
+            '$' | '`' => return Err(expanded_when_defined(character)),
+            '*' | '?' | '[' | '{' => {
+                return Err(format!(
+                    "unquoted '{character}' would expand when the alias is defined"
+                ));
+            }
+            ';' | '&' | '|' | '<' | '>' | '(' | ')' => {
+                return Err(format!(
+                    "unquoted '{character}' ends the alias definition; quote the whole value"
+                ));
+            }
+            // `~` expands to the same home directory when the alias runs.
+            _ => command.push(character),
         }
+        saw_content = true;
     }
 
-    if quote.is_some() || !saw_content {
-        return None;
+    if !saw_content {
+        return Err("alias has an empty value".into());
     }
-    Some((command, &input[consumed..]))
+    Ok((command, &input[end..]))
+}
+
+fn expanded_when_defined(character: char) -> String {
+    format!(
+        "'{character}' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs"
+    )
 }
 
 #[cfg(test)]
@@ -150,7 +432,8 @@ mod tests {
             ParsedLine::Alias {
                 name: "ll".into(),
                 command: "ls -la".into(),
-                global: false,
+                kind: AliasKind::Regular,
+                comment: None,
             }
         );
         assert_eq!(
@@ -158,7 +441,8 @@ mod tests {
             ParsedLine::Alias {
                 name: "G".into(),
                 command: "| grep".into(),
-                global: true,
+                kind: AliasKind::Global,
+                comment: Some("zsh global".into()),
             }
         );
         assert_eq!(
@@ -166,7 +450,8 @@ mod tests {
             ParsedLine::Alias {
                 name: "escaped".into(),
                 command: "ls -la".into(),
-                global: false,
+                kind: AliasKind::Regular,
+                comment: None,
             }
         );
         assert_eq!(
@@ -174,43 +459,308 @@ mod tests {
             ParsedLine::Alias {
                 name: "quoted".into(),
                 command: "echo \"hello\"".into(),
-                global: false,
+                kind: AliasKind::Regular,
+                comment: None,
             }
         );
     }
 
     #[test]
-    fn ignores_blank_and_comment_lines() {
+    fn parses_listings_from_running_shells() {
+        assert_eq!(
+            parse_alias_line(r"alias gc='git commit -m '\''wip'\'''"),
+            ParsedLine::Alias {
+                name: "gc".into(),
+                command: "git commit -m 'wip'".into(),
+                kind: AliasKind::Regular,
+                comment: None,
+            }
+        );
+        assert_eq!(
+            parse_alias_line("alias -- -='cd -'"),
+            ParsedLine::Alias {
+                name: "-".into(),
+                command: "cd -".into(),
+                kind: AliasKind::Regular,
+                comment: None,
+            }
+        );
+        assert_eq!(
+            parse_alias_line("alias -g ...=../.."),
+            ParsedLine::Alias {
+                name: "...".into(),
+                command: "../..".into(),
+                kind: AliasKind::Global,
+                comment: None,
+            }
+        );
+        assert_eq!(
+            parse_alias_line("alias -s -- txt=vim"),
+            ParsedLine::Alias {
+                name: "txt".into(),
+                command: "vim".into(),
+                kind: AliasKind::Suffix,
+                comment: None,
+            }
+        );
+        assert_eq!(
+            parse_alias_line("alias -g -s txt=vim"),
+            unsupported("alias cannot be both global and suffix")
+        );
+    }
+
+    #[test]
+    fn keeps_inert_double_quoted_text_literally() {
+        for (line, command) in [
+            (r#"alias home='echo $HOME'"#, "echo $HOME"),
+            (r#"alias gs="git status | less""#, "git status | less"),
+            (
+                r#"alias br="git branch --show-current && echo \$(date)""#,
+                "git branch --show-current && echo $(date)",
+            ),
+            (r#"alias src="ls ~/src/*.rs""#, "ls ~/src/*.rs"),
+            (r#"alias cost="echo $ 5 $""#, "echo $ 5 $"),
+            (r#"alias win="echo C:\Windows\\""#, r"echo C:\Windows\"),
+            ("alias dl=cd\\ ~/Downloads", "cd ~/Downloads"),
+            ("alias up=~/bin/update", "~/bin/update"),
+            ("alias rs='ls *.rs; echo done'", "ls *.rs; echo done"),
+        ] {
+            assert_eq!(
+                parse_alias_line(line),
+                ParsedLine::Alias {
+                    name: line[6..line.find('=').unwrap()].into(),
+                    command: command.into(),
+                    kind: AliasKind::Regular,
+                    comment: None,
+                },
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn joins_quoted_values_that_span_lines() {
+        let content = "alias ml='echo a\nalias y=b'\n# it's a comment\nalias z=c\n";
+        let lines = logical_lines(content);
+        assert_eq!(
+            lines,
+            [
+                (0, "alias ml='echo a\nalias y=b'".to_owned()),
+                (2, "# it's a comment".to_owned()),
+                (3, "alias z=c".to_owned()),
+            ]
+        );
+        assert_eq!(
+            parse_alias_line(&lines[0].1),
+            ParsedLine::Alias {
+                name: "ml".into(),
+                command: "echo a\nalias y=b".into(),
+                kind: AliasKind::Regular,
+                comment: None,
+            }
+        );
+        assert_eq!(
+            logical_lines("alias open=\"echo\nalias x=y"),
+            [(0, "alias open=\"echo\nalias x=y".to_owned())]
+        );
+    }
+
+    #[test]
+    fn separates_blank_and_comment_lines() {
         assert_eq!(parse_alias_line(""), ParsedLine::Ignored);
-        assert_eq!(parse_alias_line("  # aliases"), ParsedLine::Ignored);
+        assert_eq!(
+            parse_alias_line("  # aliases"),
+            ParsedLine::Comment("aliases".into())
+        );
+        assert_eq!(
+            parse_alias_line("alias ll='ls -la' #"),
+            ParsedLine::Alias {
+                name: "ll".into(),
+                command: "ls -la".into(),
+                kind: AliasKind::Regular,
+                comment: None,
+            }
+        );
     }
 
     #[test]
-    fn skips_unsupported_constructs() {
-        for line in [
-            "function ll() { ls -la; }",
-            "alias ll='unterminated",
-            "alias two='echo two'; echo extra",
-            "alias a='one' b='two'",
-            "alias invalid name='cmd'",
-            "alias variable=\"echo $HOME\"",
-            "alias command='safe'$(computed)",
-            "alias glob=*.rs",
-            "aliasfoo=bar",
-            "alias -global value=bar",
-            "alias ll",
+    fn section_headers_become_tags() {
+        assert_eq!(section_tag("# Git"), Some("git".into()));
+        assert_eq!(
+            section_tag("---- Docker  Compose ----"),
+            Some("docker-compose".into())
+        );
+        assert_eq!(
+            section_tag("## Build & test (local)!"),
+            Some("build-test-local".into())
+        );
+        assert_eq!(section_tag("Git"), None);
+        assert_eq!(section_tag("- not a header"), None);
+        assert_eq!(section_tag("#####"), None);
+        assert_eq!(section_tag("# ???"), None);
+        assert_eq!(section_tag("## alias old='ls'"), None);
+        assert_eq!(section_tag("alias old='ls'"), None);
+    }
+
+    #[test]
+    fn explains_unsupported_constructs() {
+        for (line, reason) in [
+            ("function ll() { ls -la; }", "not an alias declaration"),
+            ("alias ll='unterminated", "unterminated single quote"),
+            ("alias ll=\"unterminated", "unterminated double quote"),
+            (
+                "alias two='echo two'; echo extra",
+                "unquoted ';' ends the alias definition; quote the whole value",
+            ),
+            (
+                "alias a='one' b='two'",
+                "unexpected text after the alias value: 'b='two''",
+            ),
+            (
+                "alias invalid name='cmd'",
+                "invalid alias name 'invalid name'",
+            ),
+            (
+                "alias command='safe'$(computed)",
+                "'$' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs",
+            ),
+            (
+                "alias here=\"cd $PWD\"",
+                "'$' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs",
+            ),
+            (
+                "alias now=\"echo $(date)\"",
+                "'$' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs",
+            ),
+            (
+                "alias ver=\"echo ${BASH_VERSION}\"",
+                "'$' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs",
+            ),
+            (
+                "alias os=\"echo `uname`\"",
+                "'`' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs",
+            ),
+            (
+                "alias glob=*.rs",
+                "unquoted '*' would expand when the alias is defined",
+            ),
+            ("alias empty=", "alias has an empty value"),
+            ("alias next=line\\", "line continuations are not supported"),
+            ("aliasfoo=bar", "not an alias declaration"),
+            (
+                "alias -global value=bar",
+                "unsupported alias option '-global'",
+            ),
+            ("alias ll", "alias has no '=' and value"),
         ] {
-            assert_eq!(parse_alias_line(line), ParsedLine::Unsupported, "{line}");
+            assert_eq!(parse_alias_line(line), unsupported(reason), "{line}");
         }
     }
 
+    #[test]
+    fn parses_json_alias_records() {
+        let records = parse_json_aliases(
+            r#"[
+                {"name": "ll", "command": "ls -la", "enabled": false, "global": false, "tags": ["files"], "description": "List"},
+                {"name": "gs", "command": "git status", "os": ["macos"], "requires": ["git"]},
+                {"name": "bad name", "command": "ls"},
+                {"name": "tagged", "command": "ls", "tags": ["two words"]},
+                {"name": "reload", "command": "exec $SHELL", "commands": {"fish": "exec fish"}},
+                {"name": "shelled", "command": "ls", "commands": {"tcsh": "ls"}},
+                {"command": "ls"},
+                {"name": "rs", "command": "vim", "kind": "suffix"},
+                {"name": "G", "command": "| grep", "global": true}
+            ]"#,
+        )
+        .unwrap();
+
+        let (name, alias) = records[0].clone().unwrap();
+        assert_eq!(name, "ll");
+        assert!(!alias.enabled && alias.detailed);
+        assert_eq!(alias.description.as_deref(), Some("List"));
+        assert!(alias.tags.contains("files"));
+        let (_, alias) = records[1].clone().unwrap();
+        assert!(alias.enabled && alias.kind == AliasKind::Regular && alias.detailed);
+        assert!(alias.conditions.os.contains("macos") && alias.conditions.requires.contains("git"));
+        assert_eq!(records[2], Err("invalid alias name 'bad name'".into()));
+        assert_eq!(records[3], Err("invalid tag 'two words'".into()));
+        let (_, alias) = records[4].clone().unwrap();
+        assert_eq!(alias.shell_commands["fish"], "exec fish");
+        assert!(alias.detailed);
+        assert_eq!(records[5], Err("unknown shell 'tcsh' in commands".into()));
+        assert_eq!(records[6], Err("missing field `name`".into()));
+        assert_eq!(records[7].clone().unwrap().1.kind, AliasKind::Suffix);
+        assert_eq!(records[8].clone().unwrap().1.kind, AliasKind::Global);
+
+        assert!(parse_json_aliases("{}").is_err());
+        assert!(looks_like_json("\n  []"));
+        assert!(!looks_like_json("alias ll=ls"));
+    }
+
+    #[test]
+    fn same_aliases_compare_json_metadata() {
+        let mut existing = Alias::new("ls -la".into(), true, AliasKind::Regular);
+        let mut incoming = existing.clone();
+        incoming.detailed = true;
+        assert!(is_same_alias(&existing, &incoming));
+        incoming.tags.insert("files".into());
+        assert!(!is_same_alias(&existing, &incoming));
+        existing.tags.insert("files".into());
+        existing.enabled = false;
+        assert!(!is_same_alias(&existing, &incoming));
+        existing.enabled = true;
+        incoming.conditions.hosts.insert("laptop".into());
+        assert!(!is_same_alias(&existing, &incoming));
+    }
+
+    #[test]
+    fn merging_keeps_catalog_metadata_and_unions_tags() {
+        let mut existing = Alias::new("ls".into(), false, AliasKind::Regular);
+        existing.description = Some("Files".into());
+        existing.tags.insert("files".into());
+        let mut incoming = Alias::new("ls -la".into(), true, AliasKind::Regular);
+        incoming.description = Some("Long listing".into());
+        incoming.tags.insert("imported".into());
+
+        let merged = merge_alias(&existing, &incoming);
+        assert_eq!(merged.command, "ls -la");
+        assert!(!merged.enabled);
+        assert_eq!(merged.description.as_deref(), Some("Files"));
+        assert_eq!(
+            merged.tags.iter().collect::<Vec<_>>(),
+            ["files", "imported"]
+        );
+
+        existing.description = None;
+        let merged = merge_alias(&existing, &incoming);
+        assert_eq!(merged.description.as_deref(), Some("Long listing"));
+    }
+
+    #[test]
+    fn side_by_side_marks_differing_fields() {
+        let mut existing = Alias::new("ls".into(), true, AliasKind::Regular);
+        existing.tags.insert("files".into());
+        let incoming = Alias::new("ls -la".into(), true, AliasKind::Regular);
+        assert_eq!(
+            side_by_side(&existing, &incoming),
+            [
+                "              catalog | imported",
+                "* command     ls      | ls -la",
+                "  enabled     true    | true",
+                "  kind        regular | regular",
+                "* tags        files   |",
+                "  description         |",
+            ]
+        );
+    }
+
     #[test]
     fn identical_aliases_ignore_catalog_only_metadata() {
-        let mut alias = Alias::new("ls -la".into(), false, false);
+        let mut alias = Alias::new("ls -la".into(), false, AliasKind::Regular);
         alias.description = Some("Files".into());
         alias.tags.insert("shell".into());
-        assert!(is_identical(&alias, "ls -la", false));
-        assert!(!is_identical(&alias, "ls", false));
-        assert!(!is_identical(&alias, "ls -la", true));
+        assert!(is_identical(&alias, "ls -la", AliasKind::Regular));
+        assert!(!is_identical(&alias, "ls", AliasKind::Regular));
+        assert!(!is_identical(&alias, "ls -la", AliasKind::Suffix));
     }
 }
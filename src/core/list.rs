@@ -1,5 +1,6 @@
 use crate::app::shell::ShellType;
 use crate::catalog::types::AliasCatalog;
+use crate::core::sync::supports_kind;
 
 pub fn visible_aliases<'a>(
     catalog: &'a AliasCatalog,
@@ -8,5 +9,5 @@ pub fn visible_aliases<'a>(
     catalog
         .aliases
         .iter()
-        .filter(move |(_, alias)| !alias.global || *shell == ShellType::Zsh)
+        .filter(move |(_, alias)| supports_kind(alias, shell))
 }
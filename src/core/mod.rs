@@ -1,10 +1,16 @@
 pub(crate) mod add;
+pub(crate) mod condition;
 pub(crate) mod conflict;
+pub(crate) mod diff;
 pub(crate) mod disable;
 pub(crate) mod edit;
 pub(crate) mod enable;
+pub(crate) mod env;
+pub(crate) mod export;
+pub(crate) mod history;
 pub(crate) mod import;
 pub(crate) mod list;
+pub(crate) mod profile;
 pub(crate) mod remove;
 pub(crate) mod rename;
 pub(crate) mod selector;
@@ -22,6 +28,22 @@ pub enum Failure {
     InvalidCatalog,
     InvalidPattern,
     InvalidColumns,
+    SyncOutputFailed,
+    NothingToUndo,
+    NothingToRedo,
+    HistoryUnavailable,
+    HistoryOutOfDate,
+    CatalogWriteFailed,
+    ShellAliasesUnavailable,
+    ProjectFileNotFound,
+    TrustStoreUnavailable,
+    ProfileDoesNotExist,
+    ProfileUnavailable,
+    GlobalFunction,
+    UnsupportedSuffixAlias,
+    SuffixFunction,
+    InvalidVariableName,
+    VariableDoesNotExist,
 }
 
 impl std::fmt::Display for Failure {
@@ -35,6 +57,24 @@ impl std::fmt::Display for Failure {
             Self::InvalidCatalog => "catalog is invalid",
             Self::InvalidPattern => "invalid glob pattern",
             Self::InvalidColumns => "list columns must not contain duplicates",
+            Self::SyncOutputFailed => "could not write the shell synchronization script",
+            Self::NothingToUndo => "nothing to undo",
+            Self::NothingToRedo => "nothing to redo",
+            Self::HistoryUnavailable => "could not read or update the catalog history",
+            Self::HistoryOutOfDate => {
+                "catalog changed outside aliasmgr since its last recorded change"
+            }
+            Self::CatalogWriteFailed => "could not write the catalog",
+            Self::ShellAliasesUnavailable => "could not list the aliases defined by the shell",
+            Self::ProjectFileNotFound => "no .aliasmgr.toml found in the directory or its parents",
+            Self::TrustStoreUnavailable => "could not read or update the trusted projects",
+            Self::ProfileDoesNotExist => "profile does not exist",
+            Self::ProfileUnavailable => "could not read or update the active profile",
+            Self::GlobalFunction => "functions cannot be global aliases",
+            Self::UnsupportedSuffixAlias => "suffix aliases are only supported in zsh",
+            Self::SuffixFunction => "functions cannot be suffix aliases",
+            Self::InvalidVariableName => "invalid variable name",
+            Self::VariableDoesNotExist => "variable does not exist",
         };
         formatter.write_str(message)
     }
@@ -68,6 +108,52 @@ mod tests {
                 Failure::InvalidColumns,
                 "list columns must not contain duplicates",
             ),
+            (
+                Failure::SyncOutputFailed,
+                "could not write the shell synchronization script",
+            ),
+            (Failure::NothingToUndo, "nothing to undo"),
+            (Failure::NothingToRedo, "nothing to redo"),
+            (
+                Failure::HistoryUnavailable,
+                "could not read or update the catalog history",
+            ),
+            (
+                Failure::HistoryOutOfDate,
+                "catalog changed outside aliasmgr since its last recorded change",
+            ),
+            (Failure::CatalogWriteFailed, "could not write the catalog"),
+            (
+                Failure::ShellAliasesUnavailable,
+                "could not list the aliases defined by the shell",
+            ),
+            (
+                Failure::ProjectFileNotFound,
+                "no .aliasmgr.toml found in the directory or its parents",
+            ),
+            (
+                Failure::TrustStoreUnavailable,
+                "could not read or update the trusted projects",
+            ),
+            (Failure::ProfileDoesNotExist, "profile does not exist"),
+            (
+                Failure::ProfileUnavailable,
+                "could not read or update the active profile",
+            ),
+            (
+                Failure::GlobalFunction,
+                "functions cannot be global aliases",
+            ),
+            (
+                Failure::UnsupportedSuffixAlias,
+                "suffix aliases are only supported in zsh",
+            ),
+            (
+                Failure::SuffixFunction,
+                "functions cannot be suffix aliases",
+            ),
+            (Failure::InvalidVariableName, "invalid variable name"),
+            (Failure::VariableDoesNotExist, "variable does not exist"),
         ];
         for (failure, message) in cases {
             assert_eq!(failure.to_string(), message);
@@ -0,0 +1,271 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::catalog::layers::SYSTEM_LAYER;
+use crate::catalog::project::PROJECT_LAYER;
+use crate::catalog::types::AliasCatalog;
+use crate::core::validation::is_valid_tag;
+
+/// A boolean expression over alias tags, such as `work & !personal`.
+///
+/// `!` binds tightest, then `&`, then `|`; parentheses group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagExpression {
+    Tag(String),
+    Not(Box<TagExpression>),
+    And(Box<TagExpression>, Box<TagExpression>),
+    Or(Box<TagExpression>, Box<TagExpression>),
+}
+
+const OPERATORS: [char; 5] = ['!', '&', '|', '(', ')'];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Operator(char),
+    Tag(&'a str),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(character) = rest.chars().next() {
+        if OPERATORS.contains(&character) {
+            tokens.push(Token::Operator(character));
+            rest = &rest[1..];
+        } else {
+            let end = rest
+                .find(|character: char| character.is_whitespace() || OPERATORS.contains(&character))
+                .unwrap_or(rest.len());
+            let tag = &rest[..end];
+            if !is_valid_tag(tag) {
+                return Err(format!("invalid tag '{tag}'"));
+            }
+            tokens.push(Token::Tag(tag));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token<'_>> {
+        self.tokens.get(self.position)
+    }
+
+    fn eat(&mut self, operator: char) -> bool {
+        let found = self.peek() == Some(&Token::Operator(operator));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<TagExpression, String> {
+        let mut expression = self.and()?;
+        while self.eat('|') {
+            expression = TagExpression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<TagExpression, String> {
+        let mut expression = self.unary()?;
+        while self.eat('&') {
+            expression = TagExpression::And(Box::new(expression), Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<TagExpression, String> {
+        if self.eat('!') {
+            return Ok(TagExpression::Not(Box::new(self.unary()?)));
+        }
+        if self.eat('(') {
+            let expression = self.or()?;
+            if !self.eat(')') {
+                return Err("missing ')'".into());
+            }
+            return Ok(expression);
+        }
+        match self.peek() {
+            Some(Token::Tag(tag)) => {
+                let tag = (*tag).to_owned();
+                self.position += 1;
+                Ok(TagExpression::Tag(tag))
+            }
+            Some(Token::Operator(operator)) => Err(format!("unexpected '{operator}'")),
+            None => Err("expected a tag".into()),
+        }
+    }
+}
+
+impl TagExpression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+        let expression = parser.or()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(Token::Operator(operator)) => Err(format!("unexpected '{operator}'")),
+            Some(Token::Tag(tag)) => Err(format!("expected '&' or '|' before '{tag}'")),
+        }
+    }
+
+    pub fn matches(&self, tags: &BTreeSet<String>) -> bool {
+        match self {
+            Self::Tag(tag) => tags.contains(tag),
+            Self::Not(inner) => !inner.matches(tags),
+            Self::And(left, right) => left.matches(tags) && right.matches(tags),
+            Self::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Or(..) => 0,
+            Self::And(..) => 1,
+            Self::Not(_) | Self::Tag(_) => 2,
+        }
+    }
+
+    fn fmt_operand(&self, formatter: &mut fmt::Formatter<'_>, minimum: u8) -> fmt::Result {
+        if self.precedence() < minimum {
+            write!(formatter, "({self})")
+        } else {
+            write!(formatter, "{self}")
+        }
+    }
+}
+
+impl fmt::Display for TagExpression {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => formatter.write_str(tag),
+            Self::Not(inner) => {
+                formatter.write_str("!")?;
+                inner.fmt_operand(formatter, 2)
+            }
+            Self::And(left, right) => {
+                left.fmt_operand(formatter, 1)?;
+                formatter.write_str(" & ")?;
+                right.fmt_operand(formatter, 2)
+            }
+            Self::Or(left, right) => {
+                left.fmt_operand(formatter, 0)?;
+                formatter.write_str(" | ")?;
+                right.fmt_operand(formatter, 1)
+            }
+        }
+    }
+}
+
+/// Record the aliases whose tags fall outside the profile as inactive.
+///
+/// Profiles select among the user's own aliases: those supplied by the system
+/// catalog or the trusted project file always stay active.
+pub fn mark_outside_profile(catalog: &mut AliasCatalog, name: &str, profile: &TagExpression) {
+    for (alias_name, alias) in &catalog.aliases {
+        let layer = catalog.layers.get(alias_name).map(String::as_str);
+        if matches!(layer, Some(SYSTEM_LAYER | PROJECT_LAYER)) {
+            continue;
+        }
+        if !profile.matches(&alias.tags) {
+            catalog
+                .inactive
+                .entry(alias_name.clone())
+                .or_insert_with(|| format!("outside profile '{name}'"));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::catalog::types::{Alias, AliasKind};
+
+    fn tags(values: &[&str]) -> BTreeSet<String> {
+        values.iter().map(|value| (*value).to_owned()).collect()
+    }
+
+    #[test]
+    fn expressions_follow_operator_precedence() {
+        let expression = TagExpression::parse("work | shell & !personal").unwrap();
+        assert!(expression.matches(&tags(&["work", "personal"])));
+        assert!(expression.matches(&tags(&["shell"])));
+        assert!(!expression.matches(&tags(&["shell", "personal"])));
+        assert_eq!(expression.to_string(), "work | shell & !personal");
+
+        let grouped = TagExpression::parse("(work|shell)&!personal").unwrap();
+        assert!(!grouped.matches(&tags(&["work", "personal"])));
+        assert_eq!(grouped.to_string(), "(work | shell) & !personal");
+        assert_eq!(
+            TagExpression::parse("!(a & b)").unwrap().to_string(),
+            "!(a & b)"
+        );
+    }
+
+    #[test]
+    fn malformed_expressions_are_explained() {
+        for (source, reason) in [
+            ("", "expected a tag"),
+            ("work &", "expected a tag"),
+            ("(work", "missing ')'"),
+            ("work)", "unexpected ')'"),
+            ("work home", "expected '&' or '|' before 'home'"),
+            ("| work", "unexpected '|'"),
+        ] {
+            assert_eq!(TagExpression::parse(source), Err(reason.into()), "{source}");
+        }
+    }
+
+    #[test]
+    fn aliases_outside_the_profile_are_inactive() {
+        let mut catalog = AliasCatalog::new();
+        let mut work = Alias::new("make deploy".into(), true, AliasKind::Regular);
+        work.tags.insert("work".into());
+        catalog.aliases.insert("deploy".into(), work);
+        catalog.aliases.insert(
+            "ll".into(),
+            Alias::new("ls -la".into(), true, AliasKind::Regular),
+        );
+
+        mark_outside_profile(&mut catalog, "work", &TagExpression::parse("work").unwrap());
+        assert_eq!(
+            catalog.inactive.iter().collect::<Vec<_>>(),
+            [(&"ll".to_owned(), &"outside profile 'work'".to_owned())]
+        );
+
+        catalog.disable_inactive();
+        assert!(catalog.aliases["deploy"].enabled);
+        assert!(!catalog.aliases["ll"].enabled);
+    }
+
+    #[test]
+    fn system_and_project_aliases_ignore_the_profile() {
+        let mut catalog = AliasCatalog::new();
+        for (name, layer) in [
+            ("gs", "system"),
+            ("t", "project"),
+            ("ll", "user"),
+            ("db", "work"),
+        ] {
+            catalog.aliases.insert(
+                name.into(),
+                Alias::new("true".into(), true, AliasKind::Regular),
+            );
+            catalog.layers.insert(name.into(), layer.into());
+        }
+
+        mark_outside_profile(&mut catalog, "home", &TagExpression::parse("home").unwrap());
+        assert_eq!(catalog.inactive.keys().collect::<Vec<_>>(), ["db", "ll"]);
+    }
+}
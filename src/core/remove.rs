@@ -47,7 +47,7 @@ pub fn remove_tag(catalog: &mut AliasCatalog, tag: &str) -> Result<(Outcome, usi
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
     use super::*;
-    use crate::catalog::types::Alias;
+    use crate::catalog::types::{Alias, AliasKind};
 
     #[test]
     fn empty_bulk_and_all_removals_are_noops() {
@@ -59,16 +59,18 @@ mod tests {
     #[test]
     fn nonempty_bulk_and_all_removals_change_the_catalog() {
         let mut catalog = AliasCatalog::new();
-        catalog
-            .aliases
-            .insert("one".into(), Alias::new("cmd".into(), true, false));
+        catalog.aliases.insert(
+            "one".into(),
+            Alias::new("cmd".into(), true, AliasKind::Regular),
+        );
         assert_eq!(
             remove_aliases(&mut catalog, &["one".into()]),
             Outcome::CatalogChanged
         );
-        catalog
-            .aliases
-            .insert("two".into(), Alias::new("cmd".into(), true, false));
+        catalog.aliases.insert(
+            "two".into(),
+            Alias::new("cmd".into(), true, AliasKind::Regular),
+        );
         assert_eq!(remove_all(&mut catalog), Outcome::CatalogChanged);
         assert!(catalog.aliases.is_empty());
     }
@@ -50,15 +50,15 @@ pub fn aliases_with_tag(catalog: &AliasCatalog, tag: &str) -> Result<Vec<String>
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
     use super::*;
-    use crate::catalog::types::Alias;
+    use crate::catalog::types::{Alias, AliasKind};
 
     #[test]
     fn tag_filters_use_and_semantics() {
         let mut catalog = AliasCatalog::new();
-        let mut both = Alias::new("cmd".into(), true, false);
+        let mut both = Alias::new("cmd".into(), true, AliasKind::Regular);
         both.tags.extend(["dev".into(), "rust".into()]);
         catalog.aliases.insert("both".into(), both);
-        let mut one = Alias::new("cmd".into(), true, false);
+        let mut one = Alias::new("cmd".into(), true, AliasKind::Regular);
         one.tags.insert("dev".into());
         catalog.aliases.insert("one".into(), one);
 
@@ -1,94 +1,662 @@
-use crate::app::shell::{ShellType, shell_quote};
-use crate::catalog::types::{Alias, AliasCatalog};
-use crate::core::validation::is_valid_alias_name;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use crate::app::shell::{ShellType, fish_quote, nu_quote, shell_quote};
+use crate::catalog::types::{Alias, AliasCatalog, AliasKind, Variable};
+use crate::core::condition::executable_location;
+use crate::core::validation::{
+    is_identifier, is_supported_alias_name, is_valid_alias_name, is_valid_variable_name,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
 
 pub const MANAGED_ALIASES_ENV_VAR: &str = "ALIASMGR_MANAGED_ALIASES";
 pub const CATALOG_REVISION_ENV_VAR: &str = "ALIASMGR_CATALOG_REVISION";
+pub const CATALOG_STAMP_ENV_VAR: &str = "ALIASMGR_CATALOG_STAMP";
+/// Exported, unlike the rest of the terminal state, so that child shells
+/// restore the values variables had before aliasmgr managed them.
+pub const ENV_ORIGINALS_ENV_VAR: &str = "ALIASMGR_ENV_ORIGINALS";
+
+/// Prefix of every revision; bump it whenever the hashed content changes.
+const REVISION_VERSION: &str = "v1";
+
+/// 64-bit FNV-1a, which, unlike `DefaultHasher`, is stable across Rust releases
+/// and machines.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Hash a length-prefixed field so adjacent fields cannot run together.
+    fn field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// State that a terminal records after applying a reconciliation script.
+pub struct TerminalState<'a> {
+    pub managed_aliases: &'a str,
+    pub revision: &'a str,
+    pub catalog_stamp: &'a str,
+    /// The values managed variables had before aliasmgr first exported them.
+    pub env_originals: &'a str,
+}
 
 struct ActiveAlias<'a> {
     name: &'a str,
     alias: &'a Alias,
 }
 
+struct ActiveVariable<'a> {
+    name: &'a str,
+    variable: &'a Variable,
+}
+
+fn shell_command_key(shell: &ShellType) -> &'static str {
+    match shell {
+        ShellType::Bash => "bash",
+        ShellType::Zsh => "zsh",
+        ShellType::Fish => "fish",
+        ShellType::Nu => "nu",
+        ShellType::Posix => "posix",
+    }
+}
+
+/// The command an alias runs in `shell`: its `command.<shell>` variant, or
+/// the default command.
+pub fn shell_command<'a>(alias: &'a Alias, shell: &ShellType) -> &'a str {
+    alias
+        .shell_commands
+        .get(shell_command_key(shell))
+        .unwrap_or(&alias.command)
+}
+
+/// Whether the alias is a Zsh global alias in `shell`. Elsewhere, a global
+/// alias with its own command for the shell is defined as a regular alias.
+fn is_global_in(alias: &Alias, shell: &ShellType) -> bool {
+    alias.kind == AliasKind::Global && *shell == ShellType::Zsh
+}
+
+/// Distinguishes regular aliases, global aliases, functions, and suffix
+/// aliases in hashes.
+fn kind_marker(alias: &Alias, shell: &ShellType) -> u8 {
+    if alias.function {
+        2
+    } else if alias.kind == AliasKind::Suffix {
+        4
+    } else {
+        u8::from(is_global_in(alias, shell))
+    }
+}
+
+/// Whether `shell` supports the alias's kind, ignoring its name.
+///
+/// Function bodies are POSIX shell code, so fish and Nushell only define
+/// functions that have their own command for the shell. Suffix aliases have no
+/// equivalent outside Zsh, even with a command for the shell.
+pub fn supports_kind(alias: &Alias, shell: &ShellType) -> bool {
+    let has_variant = alias.shell_commands.contains_key(shell_command_key(shell));
+    if alias.function {
+        return matches!(shell, ShellType::Bash | ShellType::Zsh | ShellType::Posix) || has_variant;
+    }
+    match alias.kind {
+        AliasKind::Regular => true,
+        AliasKind::Global => *shell == ShellType::Zsh || has_variant,
+        AliasKind::Suffix => *shell == ShellType::Zsh,
+    }
+}
+
+/// Whether `shell` accepts `name` in a function definition. POSIX shells, and
+/// dash in particular, only accept identifiers, and an `eval` that fails to
+/// parse aborts the whole script in dash. Fish and Nushell quote the name.
+pub fn is_function_name_supported(name: &str, shell: &ShellType) -> bool {
+    match shell {
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => is_identifier(name),
+        ShellType::Fish | ShellType::Nu => true,
+    }
+}
+
+/// Whether a command is made of plain words, which mean the same in Nushell
+/// as in POSIX shells.
+fn is_plain_word_command(command: &str) -> bool {
+    !command.trim().is_empty()
+        && command
+            .chars()
+            .all(|character| character.is_alphanumeric() || " -_./=:,@+%".contains(character))
+}
+
+/// Why Nushell cannot define the alias, if it cannot.
+///
+/// The Nushell module holds every alias, so one command Nushell cannot parse
+/// would drop them all. Shared commands must be plain words, and anything else
+/// needs a `command.nu`, which must stay a single statement because the rest
+/// would run whenever the module is loaded. Function bodies are blocks and may
+/// hold several statements.
+pub fn nu_alias_problem(alias: &Alias) -> Option<&'static str> {
+    if alias.function {
+        return None;
+    }
+    match alias.shell_commands.get(shell_command_key(&ShellType::Nu)) {
+        Some(command) if command.trim().is_empty() || command.contains([';', '\n', '\r']) => {
+            Some("its command.nu must be a single statement without ';' or line breaks")
+        }
+        Some(_) => None,
+        None if is_plain_word_command(&alias.command) => None,
+        None => Some("its command uses shell syntax beyond plain words; add a command.nu variant"),
+    }
+}
+
+/// Whether `shell` can define the alias, ignoring whether it is enabled.
+pub fn is_definable(name: &str, alias: &Alias, shell: &ShellType) -> bool {
+    is_valid_alias_name(name)
+        && is_supported_alias_name(name, shell)
+        && supports_kind(alias, shell)
+        && (!alias.function || is_function_name_supported(name, shell))
+        && (*shell != ShellType::Nu || nu_alias_problem(alias).is_none())
+}
+
 fn active_aliases<'a>(catalog: &'a AliasCatalog, shell: &ShellType) -> Vec<ActiveAlias<'a>> {
     catalog
         .aliases
         .iter()
-        .filter(|(name, alias)| {
-            is_valid_alias_name(name)
-                && alias.enabled
-                && (!alias.global || *shell == ShellType::Zsh)
-        })
+        .filter(|(name, alias)| alias.enabled && is_definable(name, alias, shell))
         .map(|(name, alias)| ActiveAlias { name, alias })
         .collect()
 }
 
-fn catalog_revision(active: &[ActiveAlias<'_>]) -> String {
-    let mut hasher = DefaultHasher::new();
+fn active_variables(catalog: &AliasCatalog) -> Vec<ActiveVariable<'_>> {
+    catalog
+        .variables
+        .iter()
+        .filter(|(name, variable)| variable.enabled && is_valid_variable_name(name))
+        .map(|(name, variable)| ActiveVariable { name, variable })
+        .collect()
+}
+
+/// Marks variables in hashes, after the alias kinds.
+const VARIABLE_KIND: u8 = 3;
+
+fn catalog_revision(
+    active: &[ActiveAlias<'_>],
+    variables: &[ActiveVariable<'_>],
+    shell: &ShellType,
+) -> String {
+    let mut hasher = StableHasher::new();
     for entry in active {
-        (entry.name, &entry.alias.command, entry.alias.global).hash(&mut hasher);
+        hasher.field(entry.name.as_bytes());
+        hasher.field(shell_command(entry.alias, shell).as_bytes());
+        hasher.field(&[kind_marker(entry.alias, shell)]);
     }
-    format!("{:016x}", hasher.finish())
+    for entry in variables {
+        hasher.field(entry.name.as_bytes());
+        hasher.field(entry.variable.value.as_bytes());
+        hasher.field(&[VARIABLE_KIND]);
+    }
+    format!("{REVISION_VERSION}-{}", hasher.finish())
 }
 
-fn alias_command(name: &str, alias: &Alias) -> String {
-    format!(
-        "alias{} -- {}",
-        if alias.global { " -g" } else { "" },
-        shell_quote(&format!("{name}={}", alias.command))
+/// Revision of the aliases and variables that a terminal running `shell`
+/// would apply.
+pub fn effective_revision(catalog: &AliasCatalog, shell: &ShellType) -> String {
+    catalog_revision(
+        &active_aliases(catalog, shell),
+        &active_variables(catalog),
+        shell,
     )
 }
 
+/// Versioned digest of where each required executable resolves on `path`, so
+/// that a terminal notices when installing or removing one changes which
+/// aliases apply.
+pub fn executables_digest<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    path: Option<&OsStr>,
+) -> String {
+    let mut hasher = StableHasher::new();
+    for name in names {
+        hasher.field(name.as_bytes());
+        let location = executable_location(name, path).unwrap_or_default();
+        hasher.field(location.as_os_str().as_encoded_bytes());
+    }
+    format!("{REVISION_VERSION}-{}", hasher.finish())
+}
+
+fn alias_fingerprint(alias: &Alias, shell: &ShellType) -> String {
+    let mut hasher = StableHasher::new();
+    hasher.field(shell_command(alias, shell).as_bytes());
+    hasher.field(&[kind_marker(alias, shell)]);
+    hasher.finish()
+}
+
+fn variable_fingerprint(variable: &Variable) -> String {
+    let mut hasher = StableHasher::new();
+    hasher.field(variable.value.as_bytes());
+    hasher.finish()
+}
+
+/// Marks a managed name as a function rather than an alias.
+const FUNCTION_MARKER: &str = "function";
+/// Marks a managed name as a Zsh suffix alias.
+const SUFFIX_MARKER: &str = "suffix";
+/// Marks a managed name as an exported variable.
+const VARIABLE_MARKER: &str = "variable";
+
+/// How a terminal defined a managed name, which decides how it is removed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Definition {
+    Alias,
+    SuffixAlias,
+    Function,
+}
+
+impl Definition {
+    fn of(alias: &Alias) -> Self {
+        if alias.function {
+            Self::Function
+        } else if alias.kind == AliasKind::Suffix {
+            Self::SuffixAlias
+        } else {
+            Self::Alias
+        }
+    }
+
+    fn parse(marker: Option<&str>) -> Self {
+        match marker {
+            Some(FUNCTION_MARKER) => Self::Function,
+            Some(SUFFIX_MARKER) => Self::SuffixAlias,
+            _ => Self::Alias,
+        }
+    }
+
+    fn marker(self) -> Option<&'static str> {
+        match self {
+            Self::Alias => None,
+            Self::SuffixAlias => Some(SUFFIX_MARKER),
+            Self::Function => Some(FUNCTION_MARKER),
+        }
+    }
+}
+
+/// An alias or function that a terminal defined during an earlier sync.
+struct ManagedAlias<'a> {
+    fingerprint: Option<&'a str>,
+    definition: Definition,
+}
+
+/// Names that a terminal defined during earlier syncs, with the fingerprints
+/// of the variables it exported.
+#[derive(Default)]
+struct ManagedState<'a> {
+    aliases: BTreeMap<&'a str, ManagedAlias<'a>>,
+    variables: BTreeMap<&'a str, Option<&'a str>>,
+}
+
+/// Escape a value so that it fits in one tab-separated field.
+fn escape_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn unescape_field(field: &str) -> String {
+    let mut value = String::with_capacity(field.len());
+    let mut characters = field.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            value.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some(other) => value.push(other),
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+/// Parse the terminal's managed names, one `name<TAB>fingerprint` per line,
+/// followed by `<TAB>function` for functions, `<TAB>suffix` for Zsh suffix
+/// aliases, and `<TAB>variable` for variables.
+///
+/// Lines without a fingerprint come from older shell state and never match.
+fn parse_managed_state(managed_aliases: &str) -> ManagedState<'_> {
+    let mut state = ManagedState::default();
+    for line in managed_aliases.lines().filter(|line| !line.is_empty()) {
+        let mut fields = line.split('\t');
+        let name = fields.next().unwrap_or_default();
+        let fingerprint = fields.next();
+        match fields.next() {
+            Some(VARIABLE_MARKER) => {
+                state.variables.insert(name, fingerprint);
+            }
+            marker => {
+                state.aliases.insert(
+                    name,
+                    ManagedAlias {
+                        fingerprint,
+                        definition: Definition::parse(marker),
+                    },
+                );
+            }
+        }
+    }
+    state
+}
+
+/// Parse the values variables had before aliasmgr managed them, one `name`
+/// per line for variables that were unset, or `name<TAB>=value` with the
+/// value escaped.
+fn parse_env_originals(env_originals: &str) -> BTreeMap<&str, Option<String>> {
+    env_originals
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once('\t') {
+            Some((name, value)) => (name, value.strip_prefix('=').map(unescape_field)),
+            None => (line, None),
+        })
+        .collect()
+}
+
+fn format_env_originals(originals: &BTreeMap<&str, Option<String>>) -> String {
+    originals
+        .iter()
+        .map(|(name, previous)| match previous {
+            Some(previous) => format!("{name}\t={}", escape_field(previous)),
+            None => (*name).to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The shell statement that defines an alias.
+pub fn alias_definition(name: &str, alias: &Alias, shell: &ShellType) -> String {
+    let command = shell_command(alias, shell);
+    if alias.function {
+        return function_definition(name, command, shell);
+    }
+    match shell {
+        ShellType::Fish => format!("alias -- {} {}", fish_quote(name), fish_quote(command)),
+        ShellType::Nu => format!("alias {} = {command}", nu_quote(name)),
+        ShellType::Bash | ShellType::Zsh => format!(
+            "alias{} -- {}",
+            if is_global_in(alias, shell) {
+                " -g"
+            } else if alias.kind == AliasKind::Suffix {
+                " -s"
+            } else {
+                ""
+            },
+            shell_quote(&format!("{name}={command}"))
+        ),
+        // dash treats `--` as an alias name, so POSIX output relies on names
+        // never starting with a dash instead.
+        ShellType::Posix => format!("alias {}", shell_quote(&format!("{name}={command}"))),
+    }
+}
+
+/// The shell statement that defines a function running `body`.
+fn function_definition(name: &str, body: &str, shell: &ShellType) -> String {
+    match shell {
+        ShellType::Fish => format!("function {}\n{body}\nend", fish_quote(name)),
+        ShellType::Nu => format!("def {} [...args] {{\n{body}\n}}", nu_quote(name)),
+        // The `function` keyword keeps an existing alias from expanding the name.
+        ShellType::Bash | ShellType::Zsh => format!("function {name} {{\n{body}\n}}"),
+        ShellType::Posix => format!("{name}() {{\n{body}\n}}"),
+    }
+}
+
+fn alias_command(name: &str, alias: &Alias, shell: &ShellType) -> String {
+    let mut definition = alias_definition(name, alias, shell);
+    // Bash, Zsh, and fish report a malformed function body as a failure of
+    // this `eval` alone; dash still aborts the script, which aliasmgr cannot
+    // prevent without parsing the body.
+    if alias.function {
+        definition = match shell {
+            ShellType::Fish => format!("eval {}", fish_quote(&definition)),
+            ShellType::Nu => definition,
+            ShellType::Bash | ShellType::Zsh | ShellType::Posix => {
+                format!("eval {}", shell_quote(&definition))
+            }
+        };
+    }
+    match shell {
+        ShellType::Fish => format!("{definition}; or set -g __aliasmgr_sync_status $status"),
+        // A failing Nushell module is never applied, so there is no status to track.
+        ShellType::Nu => definition,
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => {
+            format!("{definition} || __aliasmgr_sync_status=$?")
+        }
+    }
+}
+
+fn unalias_command(name: &str, definition: Definition, shell: &ShellType) -> String {
+    let function = definition == Definition::Function;
+    match shell {
+        ShellType::Bash | ShellType::Zsh if function => {
+            format!("unset -f -- {} 2>/dev/null || true", shell_quote(name))
+        }
+        ShellType::Posix if function => {
+            format!("unset -f {} 2>/dev/null || true", shell_quote(name))
+        }
+        // Suffix aliases live in their own table, which plain `unalias` ignores.
+        ShellType::Zsh if definition == Definition::SuffixAlias => {
+            format!("unalias -s -- {} 2>/dev/null || true", shell_quote(name))
+        }
+        ShellType::Fish => format!("functions -e -- {} 2>/dev/null; or true", fish_quote(name)),
+        ShellType::Nu => format!("hide {}", nu_quote(name)),
+        ShellType::Bash | ShellType::Zsh => {
+            format!("unalias -- {} 2>/dev/null || true", shell_quote(name))
+        }
+        ShellType::Posix => format!("unalias {} 2>/dev/null || true", shell_quote(name)),
+    }
+}
+
+/// The shell statement that exports a variable.
+pub fn export_command(name: &str, value: &str, shell: &ShellType) -> String {
+    match shell {
+        ShellType::Fish => format!("set -gx {name} {}", fish_quote(value)),
+        ShellType::Nu => format!("$env.{name} = {}", nu_quote(value)),
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => {
+            format!("export {name}={}", shell_quote(value))
+        }
+    }
+}
+
+/// Restore the value a variable had before aliasmgr managed it.
+fn restore_command(name: &str, previous: Option<&str>, shell: &ShellType) -> String {
+    match (previous, shell) {
+        (Some(previous), _) => export_command(name, previous, shell),
+        (None, ShellType::Fish) => format!("set -e -g {name}"),
+        (None, ShellType::Nu) => format!("hide-env -i {name}"),
+        (None, ShellType::Bash | ShellType::Zsh | ShellType::Posix) => format!("unset {name}"),
+    }
+}
+
+fn state_script(shell: &ShellType, commands: &str, state: &TerminalState<'_>) -> String {
+    match shell {
+        ShellType::Fish => format!(
+            r#"set -g __aliasmgr_sync_status 0
+{commands}
+if test "$__aliasmgr_sync_status" -eq 0
+    set -g __aliasmgr_managed_aliases {}
+    set -g __aliasmgr_catalog_revision {}
+    set -g __aliasmgr_catalog_stamp {}
+    set -e -g __aliasmgr_sync_status
+else
+    set -e -g __aliasmgr_sync_status
+    false
+end"#,
+            fish_quote(state.managed_aliases),
+            fish_quote(state.revision),
+            fish_quote(state.catalog_stamp),
+        ),
+        // A Nushell module file either parses completely or is not applied at all.
+        ShellType::Nu => format!(
+            "{commands}\n$env.{MANAGED_ALIASES_ENV_VAR} = {}\n$env.{CATALOG_REVISION_ENV_VAR} = {}\n$env.{CATALOG_STAMP_ENV_VAR} = {}",
+            nu_quote(state.managed_aliases),
+            nu_quote(state.revision),
+            nu_quote(state.catalog_stamp),
+        ),
+        ShellType::Bash | ShellType::Zsh | ShellType::Posix => format!(
+            r#"__aliasmgr_sync_status=0
+{commands}
+if [ "$__aliasmgr_sync_status" -eq 0 ]; then
+    __aliasmgr_managed_aliases={}
+    __aliasmgr_catalog_revision={}
+    __aliasmgr_catalog_stamp={}
+    unset __aliasmgr_sync_status
+else
+    unset __aliasmgr_sync_status
+    false
+fi"#,
+            shell_quote(state.managed_aliases),
+            shell_quote(state.revision),
+            shell_quote(state.catalog_stamp),
+        ),
+    }
+}
+
+/// Build the script that brings a terminal from its applied state to the
+/// catalog.
+///
+/// `exported` holds the values the terminal exports for the catalog's
+/// variables. The values found before the first sync are kept in the exported
+/// originals and restored once aliasmgr stops managing the variables, even in
+/// child shells that never synced them.
 pub fn generate_reconciliation_script(
     catalog: &AliasCatalog,
     shell: &ShellType,
-    managed_aliases: &str,
-    applied_revision: &str,
+    applied: &TerminalState<'_>,
+    exported: &BTreeMap<String, String>,
+    catalog_stamp: &str,
     if_changed: bool,
 ) -> String {
     let active = active_aliases(catalog, shell);
-    let revision = catalog_revision(&active);
-    if if_changed && applied_revision == revision {
+    let variables = active_variables(catalog);
+    let revision = catalog_revision(&active, &variables, shell);
+    if if_changed && applied.revision == revision && applied.catalog_stamp == catalog_stamp {
         return String::new();
     }
 
-    let unalias_commands = managed_aliases
-        .lines()
-        .filter(|name| !name.is_empty())
-        .map(|name| format!("unalias -- {} 2>/dev/null || true", shell_quote(name)))
-        .collect::<Vec<_>>()
-        .join("\n");
-    let alias_commands = active
+    let ManagedState {
+        aliases: managed,
+        variables: managed_variables,
+    } = parse_managed_state(applied.managed_aliases);
+    let fingerprints = active
         .iter()
-        .map(|entry| {
-            format!(
-                "{} || __aliasmgr_sync_status=$?",
-                alias_command(entry.name, entry.alias)
+        .map(|entry| (entry.name, alias_fingerprint(entry.alias, shell)))
+        .collect::<BTreeMap<_, _>>();
+    // Forced syncs rebuild everything; otherwise only differing aliases are touched.
+    let is_applied = |name: &str| {
+        if_changed
+            && matches!(
+                (managed.get(name).and_then(|entry| entry.fingerprint), fingerprints.get(name)),
+                (Some(applied), Some(fingerprint)) if applied == fingerprint
             )
+    };
+
+    let commands = managed
+        .iter()
+        .filter(|(name, _)| !is_applied(name))
+        .map(|(name, entry)| unalias_command(name, entry.definition, shell))
+        .chain(
+            active
+                .iter()
+                .filter(|entry| !is_applied(entry.name))
+                .map(|entry| alias_command(entry.name, entry.alias, shell)),
+        )
+        .collect::<Vec<_>>();
+
+    let variable_fingerprints = variables
+        .iter()
+        .map(|entry| (entry.name, variable_fingerprint(entry.variable)))
+        .collect::<BTreeMap<_, _>>();
+    let is_exported = |name: &str| {
+        if_changed
+            && matches!(
+                (
+                    managed_variables.get(name).copied().flatten(),
+                    variable_fingerprints.get(name),
+                ),
+                (Some(applied), Some(fingerprint)) if applied == fingerprint
+            )
+    };
+    let mut originals = parse_env_originals(applied.env_originals);
+    let restored = managed_variables
+        .keys()
+        .chain(originals.keys())
+        .filter(|name| !variable_fingerprints.contains_key(*name))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|name| {
+            let previous = originals.get(name).cloned().flatten();
+            restore_command(name, previous.as_deref(), shell)
         })
+        .collect::<Vec<_>>();
+    let set = variables
+        .iter()
+        .filter(|entry| !is_exported(entry.name))
+        .map(|entry| export_command(entry.name, &entry.variable.value, shell));
+    // Keep the value found before the first sync, not one aliasmgr exported.
+    originals.retain(|name, _| variable_fingerprints.contains_key(name));
+    for entry in &variables {
+        originals
+            .entry(entry.name)
+            .or_insert_with(|| exported.get(entry.name).cloned());
+    }
+    let env_originals = format_env_originals(&originals);
+    let recorded = (env_originals != applied.env_originals).then(|| {
+        let value = Some(env_originals.as_str()).filter(|value| !value.is_empty());
+        restore_command(ENV_ORIGINALS_ENV_VAR, value, shell)
+    });
+    let commands = commands
+        .into_iter()
+        .chain(restored)
+        .chain(set)
+        .chain(recorded)
         .collect::<Vec<_>>()
         .join("\n");
 
     let names = active
         .iter()
-        .map(|entry| entry.name)
+        .map(|entry| {
+            let fingerprint = &fingerprints[entry.name];
+            match Definition::of(entry.alias).marker() {
+                Some(marker) => format!("{}\t{fingerprint}\t{marker}", entry.name),
+                None => format!("{}\t{fingerprint}", entry.name),
+            }
+        })
+        .chain(variables.iter().map(|entry| {
+            format!(
+                "{}\t{}\t{VARIABLE_MARKER}",
+                entry.name, variable_fingerprints[entry.name]
+            )
+        }))
         .collect::<Vec<_>>()
         .join("\n");
-    format!(
-        r#"__aliasmgr_sync_status=0
-{unalias_commands}
-{alias_commands}
-if [ "$__aliasmgr_sync_status" -eq 0 ]; then
-    __aliasmgr_managed_aliases={}
-    __aliasmgr_catalog_revision={}
-    unset __aliasmgr_sync_status
-else
-    unset __aliasmgr_sync_status
-    false
-fi"#,
-        shell_quote(&names),
-        shell_quote(&revision),
+    state_script(
+        shell,
+        &commands,
+        &TerminalState {
+            managed_aliases: &names,
+            revision: &revision,
+            catalog_stamp,
+            env_originals: &env_originals,
+        },
     )
 }
 
@@ -98,19 +666,52 @@ mod tests {
     use super::*;
 
     fn alias(command: &str) -> Alias {
-        Alias::new(command.into(), true, false)
+        Alias::new(command.into(), true, AliasKind::Regular)
+    }
+
+    fn reconcile(
+        catalog: &AliasCatalog,
+        shell: &ShellType,
+        managed_aliases: &str,
+        revision: &str,
+        if_changed: bool,
+    ) -> String {
+        let applied = TerminalState {
+            managed_aliases,
+            revision,
+            catalog_stamp: "",
+            env_originals: "",
+        };
+        generate_reconciliation_script(catalog, shell, &applied, &BTreeMap::new(), "", if_changed)
+    }
+
+    fn reconcile_variables(
+        catalog: &AliasCatalog,
+        shell: &ShellType,
+        managed_aliases: &str,
+        env_originals: &str,
+        exported: &[(&str, &str)],
+    ) -> String {
+        let applied = TerminalState {
+            managed_aliases,
+            revision: "",
+            catalog_stamp: "",
+            env_originals,
+        };
+        let exported = exported
+            .iter()
+            .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+            .collect();
+        generate_reconciliation_script(catalog, shell, &applied, &exported, "", true)
     }
 
     #[test]
     fn unchanged_revision_emits_nothing() {
         let mut catalog = AliasCatalog::new();
         catalog.aliases.insert("ll".into(), alias("ls -la"));
-        let revision = catalog_revision(&active_aliases(&catalog, &ShellType::Bash));
+        let revision = effective_revision(&catalog, &ShellType::Bash);
 
-        assert!(
-            generate_reconciliation_script(&catalog, &ShellType::Bash, "ll", &revision, true)
-                .is_empty()
-        );
+        assert!(reconcile(&catalog, &ShellType::Bash, "ll", &revision, true).is_empty());
     }
 
     #[test]
@@ -119,53 +720,301 @@ mod tests {
         catalog.aliases.insert("ll".into(), alias("ls -la"));
         catalog.aliases.insert("py".into(), alias("python3"));
 
-        let script = generate_reconciliation_script(
-            &catalog,
-            &ShellType::Bash,
-            "ll\nold",
-            "old-revision",
-            true,
-        );
+        let script = reconcile(&catalog, &ShellType::Bash, "ll\nold", "old-revision", true);
 
         assert!(script.contains("unalias -- 'll' 2>/dev/null || true"));
         assert!(script.contains("unalias -- 'old' 2>/dev/null || true"));
         assert!(script.contains("alias -- 'll=ls -la'"));
         assert!(script.contains("alias -- 'py=python3'"));
-        assert!(script.contains("__aliasmgr_managed_aliases='ll\npy'"));
+        assert!(script.contains(&format!(
+            "__aliasmgr_managed_aliases='ll\t{}\npy\t{}'",
+            alias_fingerprint(&alias("ls -la"), &ShellType::Bash),
+            alias_fingerprint(&alias("python3"), &ShellType::Bash)
+        )));
     }
 
     #[test]
-    fn force_reconciles_an_unchanged_revision() {
-        let catalog = AliasCatalog::new();
-        let revision = catalog_revision(&active_aliases(&catalog, &ShellType::Bash));
+    fn changed_catalog_only_touches_differing_aliases() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("kept".into(), alias("echo kept"));
+        catalog.aliases.insert("edited".into(), alias("echo new"));
+        catalog.aliases.insert("added".into(), alias("echo added"));
+        let managed = format!(
+            "kept\t{}\nedited\t{}\nremoved\t{}",
+            alias_fingerprint(&alias("echo kept"), &ShellType::Bash),
+            alias_fingerprint(&alias("echo old"), &ShellType::Bash),
+            alias_fingerprint(&alias("echo removed"), &ShellType::Bash)
+        );
+
+        let script = reconcile(&catalog, &ShellType::Bash, &managed, "old", true);
+        assert!(!script.contains("'kept"));
+        assert!(script.contains("unalias -- 'edited'"));
+        assert!(script.contains("unalias -- 'removed'"));
+        assert!(script.contains("alias -- 'edited=echo new'"));
+        assert!(script.contains("alias -- 'added=echo added'"));
+        assert!(script.contains("kept\t"));
+
+        let forced = reconcile(&catalog, &ShellType::Bash, &managed, "old", false);
+        assert!(forced.contains("unalias -- 'kept'"));
+        assert!(forced.contains("alias -- 'kept=echo kept'"));
+    }
+
+    #[test]
+    fn changed_stamp_with_same_revision_only_records_the_stamp() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("ll".into(), alias("ls -la"));
+        let revision = effective_revision(&catalog, &ShellType::Bash);
+        let managed = format!(
+            "ll\t{}",
+            alias_fingerprint(&alias("ls -la"), &ShellType::Bash)
+        );
+        let applied = TerminalState {
+            managed_aliases: &managed,
+            revision: &revision,
+            catalog_stamp: "1:10:7",
+            env_originals: "",
+        };
 
         assert!(
-            !generate_reconciliation_script(&catalog, &ShellType::Bash, "", &revision, false)
-                .is_empty()
+            generate_reconciliation_script(
+                &catalog,
+                &ShellType::Bash,
+                &applied,
+                &BTreeMap::new(),
+                "1:10:7",
+                true
+            )
+            .is_empty()
+        );
+        let script = generate_reconciliation_script(
+            &catalog,
+            &ShellType::Bash,
+            &applied,
+            &BTreeMap::new(),
+            "2:10:7",
+            true,
         );
+        assert!(!script.contains("alias -- "));
+        assert!(script.contains("__aliasmgr_catalog_stamp='2:10:7'"));
+    }
+
+    #[test]
+    fn force_reconciles_an_unchanged_revision() {
+        let catalog = AliasCatalog::new();
+        let revision = effective_revision(&catalog, &ShellType::Bash);
+
+        assert!(!reconcile(&catalog, &ShellType::Bash, "", &revision, false).is_empty());
     }
 
     #[test]
     fn effective_catalog_controls_revision_and_aliases() {
         let mut catalog = AliasCatalog::new();
-        catalog
-            .aliases
-            .insert("disabled".into(), Alias::new("nope".into(), false, false));
-        catalog
-            .aliases
-            .insert("global".into(), Alias::new("*.rs".into(), true, true));
+        catalog.aliases.insert(
+            "disabled".into(),
+            Alias::new("nope".into(), false, AliasKind::Regular),
+        );
+        catalog.aliases.insert(
+            "global".into(),
+            Alias::new("*.rs".into(), true, AliasKind::Global),
+        );
         catalog.aliases.insert("invalid name".into(), alias("nope"));
 
-        let bash =
-            generate_reconciliation_script(&catalog, &ShellType::Bash, "", "different", true);
+        let bash = reconcile(&catalog, &ShellType::Bash, "", "different", true);
         assert!(!bash.contains("disabled="));
         assert!(!bash.contains("global="));
         assert!(!bash.contains("invalid name="));
 
-        let zsh = generate_reconciliation_script(&catalog, &ShellType::Zsh, "", "different", true);
+        let zsh = reconcile(&catalog, &ShellType::Zsh, "", "different", true);
         assert!(zsh.contains("alias -g -- 'global=*.rs'"));
     }
 
+    #[test]
+    fn shells_use_their_own_command_variant() {
+        let mut reload = alias("exec $SHELL");
+        reload
+            .shell_commands
+            .insert("fish".into(), "exec fish".into());
+        let mut grep = Alias::new("| grep".into(), true, AliasKind::Global);
+        grep.shell_commands.insert("bash".into(), "grep".into());
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("reload".into(), reload);
+        catalog.aliases.insert("G".into(), grep);
+
+        let fish = reconcile(&catalog, &ShellType::Fish, "", "", false);
+        assert!(fish.contains("alias -- 'reload' 'exec fish'"));
+        assert!(!fish.contains("'G'"));
+
+        let bash = reconcile(&catalog, &ShellType::Bash, "", "", false);
+        assert!(bash.contains("alias -- 'reload=exec $SHELL'"));
+        assert!(bash.contains("alias -- 'G=grep'"));
+
+        let zsh = reconcile(&catalog, &ShellType::Zsh, "", "", false);
+        assert!(zsh.contains("alias -g -- 'G=| grep'"));
+        assert_ne!(
+            effective_revision(&catalog, &ShellType::Bash),
+            effective_revision(&catalog, &ShellType::Zsh)
+        );
+    }
+
+    #[test]
+    fn suffix_aliases_are_zsh_only_and_tracked_for_unalias_s() {
+        let suffix = Alias::new("vim".into(), true, AliasKind::Suffix);
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("rs".into(), suffix.clone());
+
+        let zsh = reconcile(&catalog, &ShellType::Zsh, "old\tx\tsuffix", "", true);
+        assert!(zsh.contains("unalias -s -- 'old' 2>/dev/null || true"));
+        assert!(zsh.contains("alias -s -- 'rs=vim' || __aliasmgr_sync_status=$?"));
+        assert!(zsh.contains(&format!(
+            "__aliasmgr_managed_aliases='rs\t{}\tsuffix'",
+            alias_fingerprint(&suffix, &ShellType::Zsh)
+        )));
+        assert_ne!(
+            alias_fingerprint(&suffix, &ShellType::Zsh),
+            alias_fingerprint(&alias("vim"), &ShellType::Zsh)
+        );
+
+        let mut with_variant = suffix;
+        with_variant
+            .shell_commands
+            .insert("bash".into(), "vim".into());
+        catalog.aliases.insert("rs".into(), with_variant);
+        assert!(!reconcile(&catalog, &ShellType::Bash, "", "", false).contains("rs"));
+    }
+
+    #[test]
+    fn functions_are_defined_through_eval_and_tracked_for_unset() {
+        let mut mkcd = alias("mkdir -p \"$1\" && cd \"$1\"");
+        mkcd.function = true;
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("mkcd".into(), mkcd.clone());
+
+        let script = reconcile(
+            &catalog,
+            &ShellType::Bash,
+            "old\tx\tfunction\nll\tx",
+            "",
+            true,
+        );
+        assert!(script.contains("unset -f -- 'old' 2>/dev/null || true"));
+        assert!(script.contains("unalias -- 'll' 2>/dev/null || true"));
+        assert!(script.contains(&format!(
+            "eval {} || __aliasmgr_sync_status=$?",
+            shell_quote("function mkcd {\nmkdir -p \"$1\" && cd \"$1\"\n}")
+        )));
+        assert!(script.contains(&format!(
+            "__aliasmgr_managed_aliases='mkcd\t{}\tfunction'",
+            alias_fingerprint(&mkcd, &ShellType::Bash)
+        )));
+        assert_ne!(
+            alias_fingerprint(&mkcd, &ShellType::Bash),
+            alias_fingerprint(&alias(&mkcd.command), &ShellType::Bash)
+        );
+
+        let posix = reconcile(&catalog, &ShellType::Posix, "", "", false);
+        assert!(posix.contains(&shell_quote("mkcd() {\nmkdir -p \"$1\" && cd \"$1\"\n}")));
+        assert!(!reconcile(&catalog, &ShellType::Fish, "", "", false).contains("mkcd"));
+
+        mkcd.shell_commands
+            .insert("fish".into(), "mkdir -p $argv[1]; and cd $argv[1]".into());
+        catalog.aliases.insert("mkcd".into(), mkcd);
+        catalog
+            .aliases
+            .insert("a b".into(), catalog.aliases["mkcd"].clone());
+        let fish = reconcile(&catalog, &ShellType::Fish, "", "", false);
+        assert!(fish.contains(&format!(
+            "eval {}; or set",
+            fish_quote("function 'mkcd'\nmkdir -p $argv[1]; and cd $argv[1]\nend")
+        )));
+        assert!(!is_definable(
+            "$(x)",
+            &catalog.aliases["mkcd"],
+            &ShellType::Bash
+        ));
+        assert!(!is_definable(
+            "git-up",
+            &catalog.aliases["mkcd"],
+            &ShellType::Posix
+        ));
+        assert!(is_definable(
+            "git-up",
+            &catalog.aliases["mkcd"],
+            &ShellType::Fish
+        ));
+    }
+
+    #[test]
+    fn variables_are_exported_and_restore_their_previous_value() {
+        let editor = Variable::new("nvim".into(), true);
+        let mut catalog = AliasCatalog::new();
+        catalog.variables.insert("EDITOR".into(), editor.clone());
+        catalog
+            .variables
+            .insert("PAGER".into(), Variable::new("less".into(), false));
+
+        let script = reconcile_variables(
+            &catalog,
+            &ShellType::Bash,
+            "OLD\tx\tvariable\nGONE\tx\tvariable",
+            "GONE\nOLD\t=a\\nb",
+            &[("EDITOR", "vi\tm")],
+        );
+        assert!(script.contains(
+            "unset GONE\nexport OLD='a\nb'\nexport EDITOR='nvim'\nexport ALIASMGR_ENV_ORIGINALS='EDITOR\t=vi\\tm'\n"
+        ));
+        assert!(!script.contains("PAGER"));
+        let fingerprint = variable_fingerprint(&editor);
+        let managed = format!("EDITOR\t{fingerprint}\tvariable");
+        assert!(script.contains(&format!("__aliasmgr_managed_aliases='{managed}'")));
+
+        let originals = "EDITOR\t=vi\\tm";
+        let unchanged = reconcile_variables(
+            &catalog,
+            &ShellType::Bash,
+            &managed,
+            originals,
+            &[("EDITOR", "nvim")],
+        );
+        assert!(!unchanged.contains("export EDITOR"));
+        assert!(!unchanged.contains(ENV_ORIGINALS_ENV_VAR));
+
+        catalog.variables.clear();
+        let fish = reconcile_variables(&catalog, &ShellType::Fish, &managed, originals, &[]);
+        assert!(fish.contains("set -gx EDITOR 'vi\tm'\nset -e -g ALIASMGR_ENV_ORIGINALS\n"));
+        let nu = reconcile(&catalog, &ShellType::Nu, "EDITOR\tx\tvariable", "", true);
+        assert!(nu.contains("hide-env -i EDITOR"));
+    }
+
+    #[test]
+    fn child_shells_restore_the_value_from_before_the_parent_managed_it() {
+        let mut catalog = AliasCatalog::new();
+        catalog
+            .variables
+            .insert("EDITOR".into(), Variable::new("nvim".into(), true));
+
+        // A child shell inherits the exported variable and originals, but not
+        // the parent's managed names.
+        let child = reconcile_variables(
+            &catalog,
+            &ShellType::Bash,
+            "",
+            "EDITOR\t=vi",
+            &[("EDITOR", "nvim")],
+        );
+        assert!(child.contains("export EDITOR='nvim'"));
+        assert!(!child.contains(ENV_ORIGINALS_ENV_VAR));
+
+        catalog.variables.clear();
+        let removed = reconcile_variables(
+            &catalog,
+            &ShellType::Bash,
+            "",
+            "EDITOR\t=vi",
+            &[("EDITOR", "nvim")],
+        );
+        assert!(removed.contains("export EDITOR='vi'\nunset ALIASMGR_ENV_ORIGINALS\n"));
+    }
+
     #[test]
     fn shell_values_are_safely_quoted() {
         let mut catalog = AliasCatalog::new();
@@ -173,13 +1022,120 @@ mod tests {
             .aliases
             .insert("quote'alias".into(), alias("printf '%s' \"$HOME\""));
 
-        let script =
-            generate_reconciliation_script(&catalog, &ShellType::Bash, "old'alias", "", false);
+        let script = reconcile(&catalog, &ShellType::Bash, "old'alias", "", false);
         assert!(script.contains("unalias -- 'old'\"'\"'alias'"));
         assert!(script.contains("alias -- 'quote'\"'\"'alias=printf '"));
         assert!(!script.contains("alias -- 'quote'alias="));
     }
 
+    #[test]
+    fn nushell_hides_stale_aliases_and_exports_terminal_state() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("ll".into(), alias("ls -la"));
+        catalog.aliases.insert("-dash".into(), alias("nope"));
+
+        let script = reconcile(&catalog, &ShellType::Nu, "old\nll", "", false);
+        assert_eq!(
+            script,
+            format!(
+                concat!(
+                    "hide \"ll\"\n",
+                    "hide \"old\"\n",
+                    "alias \"ll\" = ls -la\n",
+                    "$env.ALIASMGR_MANAGED_ALIASES = \"ll\\t{}\"\n",
+                    "$env.ALIASMGR_CATALOG_REVISION = \"{}\"\n",
+                    "$env.ALIASMGR_CATALOG_STAMP = \"\"",
+                ),
+                alias_fingerprint(&alias("ls -la"), &ShellType::Bash),
+                effective_revision(&catalog, &ShellType::Nu)
+            )
+        );
+    }
+
+    #[test]
+    fn nushell_skips_commands_with_posix_syntax() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert("ll".into(), alias("ls -l"));
+        catalog.aliases.insert("up".into(), alias("cd x && y"));
+        catalog
+            .aliases
+            .insert("reload".into(), alias("exec $SHELL"));
+        catalog
+            .aliases
+            .insert("log".into(), alias("tail -f ~/log 2> err"));
+
+        let nu = reconcile(&catalog, &ShellType::Nu, "", "", false);
+        assert!(nu.contains("alias \"ll\" = ls -l"));
+        for name in ["up", "reload", "log"] {
+            assert!(!nu.contains(&format!("\"{name}\"")), "{name}");
+        }
+        assert!(reconcile(&catalog, &ShellType::Bash, "", "", false).contains("cd x && y"));
+
+        let up = catalog.aliases.get_mut("up").unwrap();
+        up.shell_commands.insert("nu".into(), "cd x; y".into());
+        let nu = reconcile(&catalog, &ShellType::Nu, "", "", false);
+        assert!(!nu.contains("\"up\""));
+
+        let up = catalog.aliases.get_mut("up").unwrap();
+        up.shell_commands
+            .insert("nu".into(), "cd ($env.HOME)".into());
+        let nu = reconcile(&catalog, &ShellType::Nu, "", "", false);
+        assert!(nu.contains("alias \"up\" = cd ($env.HOME)"));
+    }
+
+    #[test]
+    fn fish_uses_functions_and_fish_quoting() {
+        let mut catalog = AliasCatalog::new();
+        catalog
+            .aliases
+            .insert("quote'alias".into(), alias(r"printf '%s\n' \\"));
+        catalog.aliases.insert("-dash".into(), alias("nope"));
+        catalog.aliases.insert(
+            "global".into(),
+            Alias::new("*.rs".into(), true, AliasKind::Global),
+        );
+
+        let script = reconcile(&catalog, &ShellType::Fish, "old", "", false);
+        assert!(script.contains("functions -e -- 'old' 2>/dev/null; or true"));
+        assert!(script.contains(r"alias -- 'quote\'alias' 'printf \'%s\\n\' \\\\'"));
+        assert!(script.contains("set -g __aliasmgr_managed_aliases 'quote\\'alias\t"));
+        assert!(!script.contains("-dash"));
+        assert!(!script.contains("global"));
+        assert!(script.trim_end().ends_with("end"));
+    }
+
+    #[test]
+    fn revisions_are_versioned_and_stable() {
+        let mut catalog = AliasCatalog::new();
+        assert_eq!(
+            effective_revision(&catalog, &ShellType::Bash),
+            "v1-cbf29ce484222325"
+        );
+
+        catalog.aliases.insert("ll".into(), alias("ls -la"));
+        assert_eq!(
+            effective_revision(&catalog, &ShellType::Bash),
+            "v1-c242ecb3ec961d77"
+        );
+        assert_eq!(
+            alias_fingerprint(&alias("ls -la"), &ShellType::Bash),
+            "a661dcaaaf807841"
+        );
+    }
+
+    #[test]
+    fn adjacent_fields_do_not_collide() {
+        let mut first = AliasCatalog::new();
+        first.aliases.insert("ab".into(), alias("c"));
+        let mut second = AliasCatalog::new();
+        second.aliases.insert("a".into(), alias("bc"));
+
+        assert_ne!(
+            effective_revision(&first, &ShellType::Bash),
+            effective_revision(&second, &ShellType::Bash)
+        );
+    }
+
     #[test]
     fn sorting_does_not_change_revision() {
         let mut first = AliasCatalog::new();
@@ -190,8 +1146,8 @@ mod tests {
         second.aliases.insert("b".into(), alias("two"));
 
         assert_eq!(
-            catalog_revision(&active_aliases(&first, &ShellType::Bash)),
-            catalog_revision(&active_aliases(&second, &ShellType::Bash))
+            effective_revision(&first, &ShellType::Bash),
+            effective_revision(&second, &ShellType::Bash)
         );
     }
 }
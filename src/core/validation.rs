@@ -1,11 +1,14 @@
 use crate::app::shell::ShellType;
-use crate::catalog::types::AliasCatalog;
+use crate::catalog::types::{AliasCatalog, AliasKind};
 use crate::core::conflict::conflict_warnings;
+use crate::core::sync::{is_function_name_supported, nu_alias_problem, supports_kind};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ValidationReport {
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
+    /// Shell limitations that do not make individual aliases wrong.
+    pub notes: Vec<String>,
 }
 
 impl ValidationReport {
@@ -18,6 +21,32 @@ pub fn is_valid_alias_name(name: &str) -> bool {
     !name.is_empty() && !name.chars().any(char::is_whitespace) && !name.contains('=')
 }
 
+pub fn is_supported_alias_name(name: &str, shell: &ShellType) -> bool {
+    match shell {
+        ShellType::Fish => !name.starts_with('-') && !name.contains('/'),
+        ShellType::Nu | ShellType::Posix => !name.starts_with('-'),
+        ShellType::Bash | ShellType::Zsh => true,
+    }
+}
+
+/// Whether every shell accepts the name as an environment variable.
+/// Prefix of the variables that hold aliasmgr's own terminal state.
+pub const RESERVED_VARIABLE_PREFIX: &str = "ALIASMGR_";
+
+pub fn is_valid_variable_name(name: &str) -> bool {
+    is_identifier(name) && !name.starts_with(RESERVED_VARIABLE_PREFIX)
+}
+
+/// Whether `name` is a shell identifier: an ASCII letter or `_`, followed by
+/// letters, digits, and `_`.
+pub fn is_identifier(name: &str) -> bool {
+    let mut characters = name.chars();
+    characters
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
 pub fn is_valid_tag(tag: &str) -> bool {
     !tag.is_empty() && tag.trim() == tag && !tag.chars().any(char::is_whitespace)
 }
@@ -26,12 +55,21 @@ pub fn validate_catalog(catalog: &AliasCatalog, shell: &ShellType) -> Validation
     let mut report = ValidationReport {
         errors: Vec::new(),
         warnings: Vec::new(),
+        notes: Vec::new(),
     };
+    if *shell == ShellType::Posix {
+        report.notes.push(
+            "POSIX shells have no prompt hook; aliases synchronize when this terminal runs aliasmgr, so edits made elsewhere apply after the next aliasmgr command or 'aliasmgr sync'.".into(),
+        );
+        report.notes.push(
+            "POSIX shells only load aliases in interactive shells that source the ENV file.".into(),
+        );
+    }
     let valid_names = catalog
         .aliases
-        .keys()
-        .filter(|name| is_valid_alias_name(name))
-        .map(String::as_str);
+        .iter()
+        .filter(|(name, alias)| is_valid_alias_name(name) && alias.kind.takes_command_position())
+        .map(|(name, _)| name.as_str());
     let conflicts = conflict_warnings(valid_names, shell);
 
     for (name, alias) in &catalog.aliases {
@@ -43,29 +81,180 @@ pub fn validate_catalog(catalog: &AliasCatalog, shell: &ShellType) -> Validation
                 report.errors.push(format!("Alias '{name}' has invalid tag '{tag}'; tags must not be empty or contain whitespace."));
             }
         }
-        if alias.global && *shell != ShellType::Zsh {
+        if is_valid_alias_name(name) && !is_supported_alias_name(name, shell) {
+            report.warnings.push(format!(
+                "Alias '{name}' cannot be defined in {shell} and will be skipped."
+            ));
+        }
+        if alias.function && is_valid_alias_name(name) && !is_function_name_supported(name, shell) {
             report.warnings.push(format!(
-                "Global alias '{name}' is unsupported in {shell} and will be skipped."
+                "Function '{name}' cannot be defined in {shell} and will be skipped: function names must be letters, digits, and '_', not starting with a digit."
+            ));
+        }
+        if !supports_kind(alias, shell) {
+            let kind = if alias.function {
+                "Function"
+            } else if alias.kind == AliasKind::Suffix {
+                "Suffix alias"
+            } else {
+                "Global alias"
+            };
+            report.warnings.push(format!(
+                "{kind} '{name}' is unsupported in {shell} and will be skipped."
+            ));
+        } else if *shell == ShellType::Nu
+            && let Some(problem) = nu_alias_problem(alias)
+        {
+            report.warnings.push(format!(
+                "Alias '{name}' cannot be defined in Nushell and will be skipped: {problem}."
             ));
         }
         if let Some(warnings) = conflicts.get(name) {
             report.warnings.extend(warnings.iter().cloned());
         }
     }
+    for (name, variable) in &catalog.variables {
+        if !is_valid_variable_name(name) {
+            report.errors.push(format!("Variable '{name}' has an invalid name; names must start with a letter or '_', contain only letters, digits, and '_', and not start with '{RESERVED_VARIABLE_PREFIX}'."));
+        }
+        for tag in &variable.tags {
+            if !is_valid_tag(tag) {
+                report.errors.push(format!("Variable '{name}' has invalid tag '{tag}'; tags must not be empty or contain whitespace."));
+            }
+        }
+    }
     report
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::catalog::types::Alias;
+    use crate::catalog::types::{Alias, Variable};
 
     #[test]
     fn invalid_tags_are_reported() {
         let mut catalog = AliasCatalog::new();
-        let mut alias = Alias::new("cmd".into(), true, false);
+        let mut alias = Alias::new("cmd".into(), true, AliasKind::Regular);
         alias.tags.insert("bad tag".into());
         catalog.aliases.insert("test".into(), alias);
         assert!(validate_catalog(&catalog, &ShellType::Zsh).errors[0].contains("bad tag"));
     }
+
+    #[test]
+    fn fish_function_name_limits_are_warnings() {
+        let mut catalog = AliasCatalog::new();
+        for name in ["-dash", "path/tool"] {
+            catalog.aliases.insert(
+                name.into(),
+                Alias::new("cmd".into(), true, AliasKind::Regular),
+            );
+        }
+        catalog.aliases.insert(
+            "global".into(),
+            Alias::new("cmd".into(), true, AliasKind::Global),
+        );
+
+        let report = validate_catalog(&catalog, &ShellType::Fish);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 3);
+        assert!(report.warnings[0].contains("cannot be defined in FISH"));
+        assert!(report.warnings[1].contains("Global alias 'global' is unsupported in FISH"));
+        assert_eq!(
+            validate_catalog(&catalog, &ShellType::Bash).warnings.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn posix_reports_degraded_features() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert(
+            "-dash".into(),
+            Alias::new("cmd".into(), true, AliasKind::Regular),
+        );
+        catalog.aliases.insert(
+            "global".into(),
+            Alias::new("cmd".into(), true, AliasKind::Global),
+        );
+
+        let report = validate_catalog(&catalog, &ShellType::Posix);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].contains("cannot be defined in POSIX"));
+        assert!(report.notes[0].contains("no prompt hook"));
+        assert!(
+            validate_catalog(&catalog, &ShellType::Bash)
+                .notes
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn function_names_must_be_plain_words() {
+        let mut catalog = AliasCatalog::new();
+        let mut function = Alias::new("echo \"$1\"".into(), true, AliasKind::Regular);
+        function.function = true;
+        catalog.aliases.insert("say".into(), function.clone());
+        catalog.aliases.insert("$(say)".into(), function.clone());
+        catalog.aliases.insert("git-up".into(), function);
+
+        assert_eq!(
+            validate_catalog(&catalog, &ShellType::Bash).warnings,
+            [
+                "Function '$(say)' cannot be defined in BASH and will be skipped: function names must be letters, digits, and '_', not starting with a digit.",
+                "Function 'git-up' cannot be defined in BASH and will be skipped: function names must be letters, digits, and '_', not starting with a digit."
+            ]
+        );
+        assert_eq!(
+            validate_catalog(&catalog, &ShellType::Posix).warnings.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn nushell_warns_about_posix_syntax() {
+        let mut catalog = AliasCatalog::new();
+        catalog.aliases.insert(
+            "up".into(),
+            Alias::new("cd x && y".into(), true, AliasKind::Regular),
+        );
+        let mut chained = Alias::new("cd x".into(), true, AliasKind::Regular);
+        chained.shell_commands.insert("nu".into(), "cd x; y".into());
+        catalog.aliases.insert("x".into(), chained);
+
+        let warnings = validate_catalog(&catalog, &ShellType::Nu).warnings;
+        assert_eq!(
+            warnings,
+            [
+                "Alias 'up' cannot be defined in Nushell and will be skipped: its command uses shell syntax beyond plain words; add a command.nu variant.",
+                "Alias 'x' cannot be defined in Nushell and will be skipped: its command.nu must be a single statement without ';' or line breaks.",
+            ]
+        );
+        assert!(
+            validate_catalog(&catalog, &ShellType::Bash)
+                .warnings
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn variable_names_must_be_identifiers() {
+        let mut catalog = AliasCatalog::new();
+        for name in [
+            "EDITOR",
+            "_private2",
+            "2FAST",
+            "MY-VAR",
+            "",
+            "ALIASMGR_ENV_ORIGINALS",
+        ] {
+            catalog
+                .variables
+                .insert(name.into(), Variable::new("value".into(), true));
+        }
+
+        let errors = validate_catalog(&catalog, &ShellType::Bash).errors;
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().all(|error| error.contains("invalid name")));
+    }
 }
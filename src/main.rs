@@ -6,14 +6,22 @@ mod cli;
 mod config;
 mod core;
 
+use cli::env::EnvAction;
 use cli::interaction::InteractionMode;
 use cli::{Cli, Commands};
 use config::load_config;
 
-use catalog::io::{catalog_path as resolve_catalog_path, load_catalog, save_catalog};
+use catalog::io::{
+    catalog_path as resolve_catalog_path, catalog_stamp, lock_catalog, parse_catalog,
+    read_catalog_content, save_catalog,
+};
 
+use catalog::layers::{CatalogLayer, USER_LAYER, catalog_layers, layers_stamp, merge_layers};
+use catalog::project::PROJECT_LAYER;
 use catalog::types::AliasCatalog;
 use core::Outcome;
+use core::condition::{Host, mark_unmet_conditions};
+use core::profile::mark_outside_profile;
 
 use app::CommandOutcome;
 use app::add::handle_add;
@@ -21,17 +29,30 @@ use app::disable::handle_disable;
 use app::doctor::handle_doctor;
 use app::edit::handle_edit;
 use app::enable::handle_enable;
+use app::env::handle_env;
+use app::export::handle_export;
 use app::file_path::{CatalogPathDecision, determine_catalog_path};
+use app::history::{
+    describe_invocation, handle_history, handle_redo, handle_undo, record_catalog_change,
+};
 use app::import::handle_import;
 use app::init::handle_init;
 use app::list::handle_list;
+use app::migrate::handle_migrate;
+use app::profile::{active_profile, handle_profile};
 use app::remove::handle_remove;
 use app::rename::handle_rename;
-use app::sync::{handle_shell_sync, handle_sync};
+use app::status::handle_status;
+use app::sync::{
+    emit_shell_sync, handle_shell_sync, handle_sync, is_catalog_unchanged, profile_stamp,
+    watched_files,
+};
+use app::trust::{current_project_file, handle_trust, handle_untrust, trusted_project_catalog};
 
 use app::shell::{DEFAULT_SHELL, determine_shell};
 
-use log::{LevelFilter, debug};
+use log::{LevelFilter, debug, warn};
+use std::path::PathBuf;
 
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn main() {
@@ -39,6 +60,15 @@ fn main() {
     if let Err(error) = cli.validate_prompt_controls() {
         error.exit();
     }
+    // Prompt hooks run this on every prompt, so skip configuration and catalog
+    // parsing when the catalog file has not changed since the last sync.
+    if let Commands::ShellSync(cmd) = &cli.command
+        && cmd.if_changed
+        && is_catalog_unchanged()
+        && emit_shell_sync("", cmd.output.as_deref()).is_ok()
+    {
+        return;
+    }
     let quiet = cli.quiet;
     let interaction_mode = if cli.yes {
         InteractionMode::Yes
@@ -85,7 +115,12 @@ fn main() {
 
     let mut catalog = AliasCatalog::new();
     let mut catalog_path = None;
+    let mut _catalog_lock = None;
+    let mut loaded_stamp = String::new();
+    let mut loaded_content = String::new();
     let mut shell = DEFAULT_SHELL;
+    let mut user_catalog_path = PathBuf::new();
+    let mut layers = Vec::new();
 
     let is_doctor = matches!(&cli.command, Commands::Doctor(_));
 
@@ -102,11 +137,51 @@ fn main() {
                 return;
             }
         };
+        user_catalog_path = resolve_catalog_path(catalog_path.as_ref());
+        layers = catalog_layers(&user_catalog_path, &config.catalog.layers);
+        let Some(write_layer) = layers.iter().find(|layer| layer.name == cli.layer) else {
+            let names = layers
+                .iter()
+                .map(|layer| layer.name.as_str())
+                .collect::<Vec<_>>();
+            eprintln!(
+                "ERROR: Unknown catalog layer '{}'; available layers: {}.",
+                cli.layer,
+                names.join(", ")
+            );
+            std::process::exit(1);
+        };
+        if write_layer.name != USER_LAYER {
+            catalog_path = Some(write_layer.path.clone());
+        }
         debug!("Using catalog path: {:?}", catalog_path);
 
         let resolved_catalog_path = resolve_catalog_path(catalog_path.as_ref());
-        catalog = match load_catalog(&resolved_catalog_path) {
-            Ok(catalog) => catalog,
+        if cli.command.modifies_catalog() {
+            _catalog_lock = match lock_catalog(&resolved_catalog_path) {
+                Ok(lock) => Some(lock),
+                Err(error) => {
+                    eprintln!(
+                        "ERROR: Could not lock catalog '{}': {error:#}",
+                        resolved_catalog_path.display()
+                    );
+                    std::process::exit(1);
+                }
+            };
+        }
+        loaded_stamp = catalog_stamp(&resolved_catalog_path);
+        // Migration reads catalogs that this version can no longer parse.
+        let is_migrate = matches!(cli.command, Commands::Migrate(_));
+        let loaded = read_catalog_content(&resolved_catalog_path).and_then(|content| {
+            let catalog = if is_migrate {
+                AliasCatalog::new()
+            } else {
+                parse_catalog(&content)?
+            };
+            Ok((catalog, content))
+        });
+        (catalog, loaded_content) = match loaded {
+            Ok(loaded) => loaded,
             Err(error) => {
                 eprintln!(
                     "ERROR: Could not load catalog '{}': {error}",
@@ -118,24 +193,103 @@ fn main() {
         debug!("Loaded catalog: {:?}", catalog);
     }
 
+    // Read-only commands see every layer; later layers override earlier ones.
+    let project_file = cli
+        .command
+        .reads_all_layers()
+        .then(current_project_file)
+        .flatten();
+    let mut effective = if cli.command.reads_all_layers() {
+        let project = project_file
+            .as_deref()
+            .and_then(|file| trusted_project_catalog(&user_catalog_path, file));
+        load_layers(&layers, &cli.layer, &catalog, project)
+    } else {
+        catalog.clone()
+    };
+    let profile = cli
+        .command
+        .reads_all_layers()
+        .then(|| active_profile(&config, &user_catalog_path))
+        .flatten();
+    // Conditions and profiles decide which aliases shells apply here, not
+    // what the catalog holds.
+    if matches!(
+        cli.command,
+        Commands::List(_) | Commands::Status(_) | Commands::ShellSync(_)
+    ) {
+        mark_unmet_conditions(&mut effective, &Host::current());
+        if let Some((name, expression)) = &profile {
+            mark_outside_profile(&mut effective, name, expression);
+        }
+    }
+    if matches!(cli.command, Commands::Status(_) | Commands::ShellSync(_)) {
+        effective.disable_inactive();
+    }
+
     let result = match cli.command {
         Commands::Add(cmd) => {
             handle_add(&mut catalog, cmd, &shell, interaction_mode).map(CommandOutcome::from)
         }
         Commands::Remove(cmd) => handle_remove(&mut catalog, cmd, interaction_mode),
         Commands::List(cmd) => {
-            handle_list(&catalog, cmd, &shell, &config, colors_enabled).map(CommandOutcome::from)
+            let profile = profile
+                .as_ref()
+                .map(|(name, expression)| (name.as_str(), *expression));
+            handle_list(&effective, cmd, &shell, &config, profile, colors_enabled)
+                .map(CommandOutcome::from)
         }
         Commands::Rename(cmd) => handle_rename(&mut catalog, cmd),
         Commands::Edit(cmd) => handle_edit(&mut catalog, cmd, &shell).map(CommandOutcome::from),
+        Commands::Export(cmd) => handle_export(&effective, cmd).map(CommandOutcome::from),
         Commands::Import(cmd) => handle_import(&mut catalog, cmd, interaction_mode),
         Commands::Enable(cmd) => handle_enable(&mut catalog, cmd),
         Commands::Disable(cmd) => handle_disable(&mut catalog, cmd),
         Commands::Doctor(_) => handle_doctor(&catalog, &shell, quiet).map(CommandOutcome::from),
+        Commands::Undo(_) => handle_undo(
+            &resolve_catalog_path(catalog_path.as_ref()),
+            &loaded_content,
+            &loaded_stamp,
+        ),
+        Commands::Redo(_) => handle_redo(
+            &resolve_catalog_path(catalog_path.as_ref()),
+            &loaded_content,
+            &loaded_stamp,
+        ),
+        Commands::History(_) => handle_history(&resolve_catalog_path(catalog_path.as_ref())),
+        Commands::Migrate(cmd) => handle_migrate(
+            &resolve_catalog_path(catalog_path.as_ref()),
+            &loaded_content,
+            &loaded_stamp,
+            cmd,
+            interaction_mode,
+            quiet,
+        ),
+        Commands::Profile(cmd) => handle_profile(&config, &user_catalog_path, cmd),
+        // Listing shows the variables of every layer, like `list` does for aliases.
+        Commands::Env(cmd) => match cmd.action {
+            EnvAction::List { .. } => handle_env(&mut effective, cmd),
+            _ => handle_env(&mut catalog, cmd),
+        },
+        Commands::Trust(cmd) => handle_trust(&user_catalog_path, cmd),
+        Commands::Untrust(cmd) => handle_untrust(&user_catalog_path, cmd),
         Commands::Sync(_) => handle_sync().map(CommandOutcome::from),
+        Commands::Status(cmd) => handle_status(
+            &effective,
+            &shell,
+            &resolve_catalog_path(catalog_path.as_ref()),
+            cmd,
+        ),
         Commands::ShellSync(cmd) => {
-            print!("{}", handle_shell_sync(&catalog, &shell, cmd));
-            Ok(CommandOutcome::from(Outcome::NoChanges))
+            let output = cmd.output.clone();
+            let stamp = profile_stamp(layers_stamp(
+                &user_catalog_path,
+                &watched_files(&user_catalog_path),
+                &config.catalog.layers,
+                project_file.as_deref(),
+            ));
+            let script = handle_shell_sync(&effective, &shell, &stamp, cmd);
+            emit_shell_sync(&script, output.as_deref()).map(CommandOutcome::from)
         }
         Commands::Init(cmd) => {
             let content = handle_init(cmd);
@@ -150,13 +304,26 @@ fn main() {
             match outcome {
                 Outcome::NoChanges => debug!("No changes made to catalog or shell."),
                 Outcome::CatalogChanged => {
-                    if save_catalog(&mut catalog, &resolve_catalog_path(catalog_path.as_ref()))
-                        .is_err()
-                    {
-                        eprintln!("Failed to save updated catalog.");
-                        return;
-                    }
+                    let resolved_catalog_path = resolve_catalog_path(catalog_path.as_ref());
+                    let saved_content = match save_catalog(
+                        &mut catalog,
+                        &resolved_catalog_path,
+                        config.catalog.sort,
+                        &loaded_stamp,
+                    ) {
+                        Ok(content) => content,
+                        Err(error) => {
+                            eprintln!("ERROR: Failed to save updated catalog: {error:#}");
+                            std::process::exit(1);
+                        }
+                    };
                     debug!("New catalog saved.");
+                    record_catalog_change(
+                        &resolved_catalog_path,
+                        describe_invocation(std::env::args().skip(1)),
+                        &loaded_content,
+                        &saved_content,
+                    );
                 }
             }
 
@@ -175,3 +342,34 @@ fn main() {
         }
     }
 }
+
+/// Merge every layer, using the already loaded catalog for the layer being
+/// changed, with the trusted project catalog on top. Layers that cannot be read
+/// are skipped with a warning.
+fn load_layers(
+    layers: &[CatalogLayer],
+    loaded_layer: &str,
+    loaded: &AliasCatalog,
+    project: Option<AliasCatalog>,
+) -> AliasCatalog {
+    let catalogs = layers.iter().filter_map(|layer| {
+        if layer.name == loaded_layer {
+            return Some((layer.name.as_str(), loaded.clone()));
+        }
+        if !layer.path.exists() {
+            return None;
+        }
+        match read_catalog_content(&layer.path).and_then(|content| parse_catalog(&content)) {
+            Ok(catalog) => Some((layer.name.as_str(), catalog)),
+            Err(error) => {
+                warn!(
+                    "Skipping catalog layer '{}' ('{}'): {error}",
+                    layer.name,
+                    layer.path.display()
+                );
+                None
+            }
+        }
+    });
+    merge_layers(catalogs.chain(project.map(|project| (PROJECT_LAYER, project))))
+}
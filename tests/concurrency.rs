@@ -0,0 +1,40 @@
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn concurrent_adds_do_not_lose_updates() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    fs::write(&catalog, "").unwrap();
+
+    let children = (0..8)
+        .map(|index| {
+            Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
+                .args(["add", &format!("alias{index}"), &format!("echo {index}")])
+                .env("ALIASMGR_CATALOG_PATH", &catalog)
+                .env("ALIASMGR_SHELL", "bash")
+                .env("PATH", "")
+                .spawn()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let saved = fs::read_to_string(&catalog).unwrap();
+    for index in 0..8 {
+        assert!(
+            saved.contains(&format!("alias{index} = \"echo {index}\"")),
+            "{saved}"
+        );
+    }
+    let names = fs::read_dir(directory.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    assert!(
+        names.iter().all(|name| !name.ends_with(".tmp")),
+        "{names:?}"
+    );
+}
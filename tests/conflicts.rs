@@ -45,6 +45,30 @@ fn add_warns_for_executable_on_path() {
     )));
 }
 
+#[test]
+fn suffix_aliases_do_not_warn_about_executables() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let executable = directory.path().join("sh");
+    let fake_zsh = directory.path().join("zsh");
+    fs::write(&catalog, "").unwrap();
+    make_executable(&executable, "executable");
+    make_executable(&fake_zsh, "#!/bin/sh\n");
+
+    for args in [
+        &["add", "sh", "less", "--suffix"][..],
+        &["edit", "sh", "--description", "Shell scripts"],
+        &["doctor"],
+    ] {
+        let output = run_aliasmgr(&catalog, "zsh", directory.path(), args);
+        assert!(output.status.success(), "{output:?}");
+        assert!(
+            !String::from_utf8_lossy(&output.stderr).contains("shadows"),
+            "{output:?}"
+        );
+    }
+}
+
 #[test]
 fn add_warns_for_bash_builtin() {
     let directory = tempfile::tempdir().unwrap();
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_aliasmgr(catalog: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
+        .args(args)
+        .env("ALIASMGR_CATALOG_PATH", catalog)
+        .env("ALIASMGR_SHELL", "bash")
+        .env("PATH", "")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn remove_all_can_be_undone_redone_and_reviewed() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    fs::write(&catalog, "# personal aliases\n").unwrap();
+
+    stdout(run_aliasmgr(&catalog, &["add", "ll", "ls -la"]));
+    stdout(run_aliasmgr(&catalog, &["add", "gs", "git status"]));
+    let populated = fs::read_to_string(&catalog).unwrap();
+    stdout(run_aliasmgr(&catalog, &["remove", "all", "--yes"]));
+
+    assert_eq!(
+        stdout(run_aliasmgr(&catalog, &["undo"])),
+        "Undid: remove all --yes\n  + gs: git status\n  + ll: ls -la\n"
+    );
+    assert_eq!(fs::read_to_string(&catalog).unwrap(), populated);
+
+    let history = stdout(run_aliasmgr(&catalog, &["history"]));
+    assert!(history.starts_with("3. just now (undone): remove all --yes\n"));
+    assert!(history.contains("2. just now: add gs 'git status'\n   + gs: git status\n"));
+
+    stdout(run_aliasmgr(&catalog, &["redo"]));
+    assert!(!fs::read_to_string(&catalog).unwrap().contains("ll"));
+    let output = run_aliasmgr(&catalog, &["redo"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nothing to redo"));
+}
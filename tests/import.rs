@@ -1,6 +1,7 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 fn run_aliasmgr(catalog: &Path, args: &[&str]) -> Output {
     Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
@@ -222,3 +223,268 @@ fn all_unreadable_sources_still_succeed_without_changes() {
     );
     assert_eq!(fs::read_to_string(&catalog).unwrap(), original);
 }
+
+#[test]
+fn imports_alias_listings_from_standard_input() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    fs::write(&catalog, "").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
+        .args(["import", "-"])
+        .env("ALIASMGR_CATALOG_PATH", &catalog)
+        .env("ALIASMGR_SHELL", "zsh")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            concat!(
+                "alias -- -='cd -'\n",
+                "alias -g G='| grep'\n",
+                "alias -s txt=vim\n",
+                "alias gc='git commit -m '\\''wip'\\'''\n",
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Imported 4 aliases."
+    );
+    let content = fs::read_to_string(&catalog).unwrap();
+    assert!(content.contains("- = \"cd -\""));
+    assert!(content.contains("G = { command = \"| grep\", enabled = true, global = true"));
+    assert!(content.contains("txt = { command = \"vim\", enabled = true, kind = \"suffix\" }"));
+    assert!(content.contains("gc = \"git commit -m 'wip'\""));
+}
+
+#[test]
+fn comments_become_descriptions_and_sections_become_tags() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let source = directory.path().join("bash_aliases");
+    fs::write(&catalog, "").unwrap();
+    fs::write(
+        &source,
+        concat!(
+            "alias top=htop\n",
+            "# ---- Git ----\n",
+            "alias gs='git status' # show status\n",
+            "\n",
+            "alias gl='git log'\n",
+            "## File listing\n",
+            "# alias old='ls'\n",
+            "alias ll='ls -la' # list files\n",
+        ),
+    )
+    .unwrap();
+
+    let output = run_aliasmgr(
+        &catalog,
+        &[
+            "import",
+            source.to_str().unwrap(),
+            "--comment-descriptions",
+            "--section-tags",
+        ],
+    );
+
+    assert!(output.status.success(), "{output:?}");
+    let content = fs::read_to_string(&catalog).unwrap();
+    assert!(content.contains("top = \"htop\""), "{content}");
+    assert!(content.contains("description = \"show status\", tags = [\"git\"]"));
+    assert!(content.contains(
+        "gl = { command = \"git log\", enabled = true, global = false, tags = [\"git\"]"
+    ));
+    assert!(content.contains("description = \"list files\", tags = [\"file-listing\"]"));
+}
+
+#[test]
+fn verbose_import_explains_each_skipped_line() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let source = directory.path().join("aliases");
+    fs::write(&catalog, "").unwrap();
+    fs::write(
+        &source,
+        concat!(
+            "alias gs=\"git status | less\"\n",
+            "alias src=*.rs\n",
+            "alias here=\"cd $PWD\"\n",
+            "alias home='cd $HOME'\n",
+        ),
+    )
+    .unwrap();
+
+    let output = run_aliasmgr(&catalog, &["import", source.to_str().unwrap(), "--verbose"]);
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "{}:2: unquoted '*' would expand when the alias is defined; skipping line.",
+        source.display()
+    )));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "{}:3: '$' would expand when the alias is defined; quote it with single quotes to expand it when the alias runs; skipping line.",
+        source.display()
+    )));
+    let content = fs::read_to_string(&catalog).unwrap();
+    assert!(!content.contains("here"), "{content}");
+    assert!(content.contains("gs = \"git status | less\""), "{content}");
+    assert!(content.contains("home = \"cd $HOME\""), "{content}");
+}
+
+#[test]
+fn json_lists_round_trip_through_import() {
+    let directory = tempfile::tempdir().unwrap();
+    let source = directory.path().join("source.toml");
+    let catalog = directory.path().join("aliases.toml");
+    let listing = directory.path().join("aliases.json");
+    fs::write(
+        &source,
+        concat!(
+            "ll = { command = \"ls -la\", enabled = false, global = false, description = \"Files\", tags = [\"files\"] }\n",
+            "G = { command = \"| grep\", enabled = true, global = true }\n",
+            "gs = \"git status\"\n",
+        ),
+    )
+    .unwrap();
+    let exported = run_aliasmgr(&source, &["list", "--all", "--format", "json"]);
+    assert!(exported.status.success(), "{exported:?}");
+    fs::write(&listing, &exported.stdout).unwrap();
+    fs::write(&catalog, "").unwrap();
+
+    let imported = run_aliasmgr(&catalog, &["import", listing.to_str().unwrap()]);
+    assert!(imported.status.success(), "{imported:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&imported.stdout).trim(),
+        "Imported 3 aliases."
+    );
+    let reexported = run_aliasmgr(&catalog, &["list", "--all", "--format", "json"]);
+    assert_eq!(reexported.stdout, exported.stdout);
+
+    // Changed metadata is a collision even when the command is unchanged.
+    let edited = String::from_utf8_lossy(&exported.stdout).replace("\"Files\"", "\"All files\"");
+    fs::write(&listing, edited).unwrap();
+    let replaced = run_aliasmgr(
+        &catalog,
+        &[
+            "import",
+            "--format",
+            "json",
+            listing.to_str().unwrap(),
+            "-r",
+        ],
+    );
+    assert!(replaced.status.success(), "{replaced:?}");
+    assert!(
+        String::from_utf8_lossy(&replaced.stdout)
+            .contains("1 collisions found; 1 replaced and 0 skipped; 2 aliases unchanged")
+    );
+    assert!(
+        fs::read_to_string(&catalog)
+            .unwrap()
+            .contains("description = \"All files\"")
+    );
+
+    fs::write(
+        &listing,
+        r#"[{"name": "bad name", "command": "ls"}, {"name": "ok", "command": "true"}]"#,
+    )
+    .unwrap();
+    let partial = run_aliasmgr(&catalog, &["import", listing.to_str().unwrap()]);
+    assert_eq!(
+        String::from_utf8_lossy(&partial.stdout).trim(),
+        "Imported 1 aliases; 1 unsupported entries skipped."
+    );
+
+    fs::write(&listing, "alias ll='ls'\n").unwrap();
+    let invalid = run_aliasmgr(
+        &catalog,
+        &["import", "--format", "json", listing.to_str().unwrap()],
+    );
+    assert!(invalid.status.success(), "{invalid:?}");
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("skipping file"));
+}
+
+#[test]
+fn json_report_lists_every_imported_line() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let source = directory.path().join("aliases");
+    fs::write(&catalog, "ll = \"ls\"\ngs = \"git status\"\n").unwrap();
+    fs::write(
+        &source,
+        "# Files\nalias ll='ls -la'\nalias gs='git status'\nalias src=*.rs\nalias gd='git diff'\n",
+    )
+    .unwrap();
+
+    let output = run_aliasmgr(
+        &catalog,
+        &["import", source.to_str().unwrap(), "-s", "-o", "json"],
+    );
+
+    assert!(output.status.success(), "{output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let source = source.display().to_string();
+    assert_eq!(
+        report,
+        serde_json::json!([
+            {"source": source, "line": 2, "name": "ll", "result": "skipped-collision", "reason": "an alias with this name is already in the catalog"},
+            {"source": source, "line": 3, "name": "gs", "result": "unchanged", "reason": null},
+            {"source": source, "line": 4, "name": null, "result": "unsupported", "reason": "unquoted '*' would expand when the alias is defined"},
+            {"source": source, "line": 5, "name": "gd", "result": "imported", "reason": null},
+        ])
+    );
+}
+
+#[test]
+fn merge_and_rename_keep_catalog_metadata_and_incoming_aliases() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let source = directory.path().join("aliases");
+    let original = "ll = { command = \"ls\", enabled = true, global = false, description = \"Files\", tags = [\"files\"] }\n";
+    fs::write(&catalog, original).unwrap();
+    fs::write(&source, "## Listing\nalias ll='ls -la'\n").unwrap();
+
+    let merged = run_aliasmgr(
+        &catalog,
+        &["import", source.to_str().unwrap(), "-m", "--section-tags"],
+    );
+    assert!(merged.status.success(), "{merged:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&merged.stdout).trim(),
+        "Imported 0 aliases; 1 collisions found; 0 replaced, 1 merged and 0 skipped."
+    );
+    assert_eq!(
+        fs::read_to_string(&catalog).unwrap(),
+        "ll = { command = \"ls -la\", enabled = true, global = false, description = \"Files\", tags = [\"files\", \"listing\"] }\n"
+    );
+
+    fs::write(&catalog, original).unwrap();
+    for _ in 0..2 {
+        let renamed = run_aliasmgr(
+            &catalog,
+            &[
+                "import",
+                source.to_str().unwrap(),
+                "-s",
+                "--rename-suffix",
+                "-imported",
+            ],
+        );
+        assert!(renamed.status.success(), "{renamed:?}");
+    }
+    let content = fs::read_to_string(&catalog).unwrap();
+    assert!(content.starts_with(original), "{content}");
+    assert!(content.contains("ll-imported = \"ls -la\""), "{content}");
+    assert!(!content.contains("ll-imported2"), "{content}");
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_aliasmgr(directory: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
+        .env("ALIASMGR_CATALOG_PATH", directory.join("aliases.toml"))
+        .env("ALIASMGR_CONFIG_PATH", directory.join("config.toml"))
+        .env(
+            "ALIASMGR_SYSTEM_CATALOG_PATH",
+            directory.join("system.toml"),
+        )
+        .env("ALIASMGR_SHELL", "bash")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn layers_stack_in_order_and_writes_go_to_the_chosen_layer() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path();
+    fs::write(
+        path.join("config.toml"),
+        "[[catalog.layers]]\nname = \"work\"\npath = \"work.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        path.join("system.toml"),
+        "ll = \"ls -l\"\ngs = \"git status\"\n",
+    )
+    .unwrap();
+    fs::write(path.join("aliases.toml"), "ll = \"ls -la\"\n").unwrap();
+    fs::write(path.join("work.toml"), "gs = \"git status -sb\"\n").unwrap();
+
+    let list = run_aliasmgr(path, &["list", "--columns", "name,command,layer"]);
+    assert!(list.status.success());
+    assert_eq!(
+        String::from_utf8(list.stdout).unwrap(),
+        "Name  Command         Layer\n\
+         gs    git status -sb  work\n\
+         ll    ls -la          user\n"
+    );
+
+    assert!(run_aliasmgr(path, &["add", "mk", "make"]).status.success());
+    assert!(
+        fs::read_to_string(path.join("aliases.toml"))
+            .unwrap()
+            .contains("mk = \"make\"")
+    );
+
+    let added = run_aliasmgr(path, &["--layer", "work", "add", "dp", "make deploy"]);
+    assert!(added.status.success());
+    assert!(
+        fs::read_to_string(path.join("work.toml"))
+            .unwrap()
+            .contains("dp = \"make deploy\"")
+    );
+
+    let unknown = run_aliasmgr(path, &["--layer", "home", "list"]);
+    assert!(!unknown.status.success());
+    assert!(
+        String::from_utf8(unknown.stderr)
+            .unwrap()
+            .contains("Unknown catalog layer 'home'")
+    );
+}
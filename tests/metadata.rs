@@ -76,6 +76,22 @@ fn metadata_commands_cover_exact_shorthand_tag_filter_and_all_forms() {
     assert_eq!(one["enabled"], false);
     assert_eq!(one["global"], false);
 
+    let suffix_in_bash = run_aliasmgr_with_shell(&catalog, "bash", &["edit", "one", "--suffix"]);
+    assert!(!suffix_in_bash.status.success(), "{suffix_in_bash:?}");
+    let suffix = run_aliasmgr_with_shell(&catalog, "zsh", &["edit", "one", "--suffix"]);
+    assert!(suffix.status.success(), "{suffix:?}");
+    let listed = run_aliasmgr_with_shell(
+        &catalog,
+        "zsh",
+        &["list", "--suffix", "--all", "--format", "json"],
+    );
+    let aliases: serde_json::Value = serde_json::from_slice(&listed.stdout).unwrap();
+    assert_eq!(aliases[0]["command"], "echo one");
+    assert_eq!(aliases[0]["kind"], "suffix");
+    assert_eq!(aliases[0]["global"], false);
+    let suffix_off = run_aliasmgr_with_shell(&catalog, "zsh", &["edit", "one", "--no-suffix"]);
+    assert!(suffix_off.status.success(), "{suffix_off:?}");
+
     let remove_tag = run_aliasmgr(&catalog, &["remove", "tag", "dev"]);
     assert!(remove_tag.status.success(), "{remove_tag:?}");
     let remove_exact = run_aliasmgr(&catalog, &["remove", "alias", "two"]);
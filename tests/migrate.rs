@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_aliasmgr(catalog: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
+        .args(args)
+        .env("ALIASMGR_CATALOG_PATH", catalog)
+        .env("ALIASMGR_SHELL", "bash")
+        .env("PATH", "")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const LEGACY: &str = "ll = \"ls -la\"\n\n[dev]\nenabled = false\nbuild = \"cargo build\"\n";
+
+#[test]
+fn legacy_catalogs_are_migrated_with_a_backup() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    fs::write(&catalog, LEGACY).unwrap();
+
+    let rejected = run_aliasmgr(&catalog, &["list"]);
+    assert!(!rejected.status.success());
+    assert!(String::from_utf8_lossy(&rejected.stderr).contains("run 'aliasmgr migrate'"));
+
+    let preview = stdout(run_aliasmgr(&catalog, &["migrate", "--dry-run"]));
+    assert!(preview.contains("from format version 1 to 2:\n+version = 2\n"));
+    assert!(preview.contains("-[dev]\n"));
+    assert!(preview.contains(
+        "+build = { command = \"cargo build\", enabled = false, global = false, tags = [\"dev\"] }\n"
+    ));
+    assert!(preview.ends_with("Dry run: the catalog was not changed.\n"));
+    assert_eq!(fs::read_to_string(&catalog).unwrap(), LEGACY);
+
+    let migrated = stdout(run_aliasmgr(&catalog, &["migrate", "--yes"]));
+    let backup = directory.path().join("aliases.toml.v1.bak");
+    assert!(migrated.contains(&format!("The original was saved to '{}'", backup.display())));
+    assert_eq!(fs::read_to_string(&backup).unwrap(), LEGACY);
+
+    let list = stdout(run_aliasmgr(
+        &catalog,
+        &["list", "--disabled", "--format", "json"],
+    ));
+    assert!(list.contains("\"build\""));
+    assert!(stdout(run_aliasmgr(&catalog, &["migrate"])).contains("nothing to migrate"));
+}
+
+#[test]
+fn declining_leaves_the_catalog_untouched() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    fs::write(&catalog, LEGACY).unwrap();
+
+    let declined = stdout(run_aliasmgr(&catalog, &["migrate", "--no"]));
+    assert!(declined.ends_with("Migration declined. No changes made.\n"));
+    assert_eq!(fs::read_to_string(&catalog).unwrap(), LEGACY);
+    assert!(!directory.path().join("aliases.toml.v1.bak").exists());
+}
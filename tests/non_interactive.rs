@@ -152,19 +152,25 @@ fn yes_does_not_force_shell_reconciliation() {
     let forced = run_aliasmgr(&catalog, &["shell-sync", "--force"]);
     assert!(forced.status.success(), "{forced:?}");
     let script = String::from_utf8(forced.stdout).unwrap();
-    let revision = script
-        .lines()
-        .find_map(|line| {
-            line.strip_prefix("    __aliasmgr_catalog_revision='")
-                .and_then(|value| value.strip_suffix('\''))
-        })
-        .unwrap();
+    let state = |variable: &str| {
+        script
+            .lines()
+            .find_map(|line| {
+                line.strip_prefix(&format!("    {variable}='"))
+                    .and_then(|value| value.strip_suffix('\''))
+            })
+            .unwrap()
+    };
 
     let if_changed = Command::new(env!("CARGO_BIN_EXE_aliasmgr"))
         .args(["--yes", "shell-sync", "--if-changed"])
         .env("ALIASMGR_CATALOG_PATH", &catalog)
         .env("ALIASMGR_SHELL", "bash")
-        .env("ALIASMGR_CATALOG_REVISION", revision)
+        .env(
+            "ALIASMGR_CATALOG_REVISION",
+            state("__aliasmgr_catalog_revision"),
+        )
+        .env("ALIASMGR_CATALOG_STAMP", state("__aliasmgr_catalog_stamp"))
         .output()
         .unwrap();
     assert!(if_changed.status.success(), "{if_changed:?}");
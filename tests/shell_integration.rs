@@ -17,6 +17,8 @@ fn run_shell(shell: &str, script: &str, catalog: &Path) -> std::io::Result<Outpu
     match shell {
         "bash" => command.args(["--noprofile", "--norc", "-c", script, "aliasmgr-test"]),
         "zsh" => command.args(["-f", "-c", script, "aliasmgr-test"]),
+        "dash" => command.args(["-c", script, "aliasmgr-test"]),
+        "fish" => command.args(["--no-config", "-c", script]),
         _ => unreachable!(),
     };
     command
@@ -105,6 +107,75 @@ aliasmgr add top 'echo top'
     assert_success(run_shell("bash", script, catalog.path()).unwrap());
 }
 
+#[test]
+fn function_aliases_take_arguments_and_are_removed_with_unset() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2")"
+aliasmgr add greet 'echo "hello $1, and $#"' --function
+__aliasmgr_prompt_sync
+[ "$(greet world two)" = 'hello world, and 2' ] || exit 60
+aliasmgr edit greet --no-function 'echo hi'
+__aliasmgr_prompt_sync
+! declare -F greet >/dev/null || exit 61
+alias greet | command grep -q 'echo hi' || exit 62
+aliasmgr edit greet --function 'echo "bye $1"'
+__aliasmgr_prompt_sync
+! alias greet 2>/dev/null || exit 63
+[ "$(greet you)" = 'bye you' ] || exit 64
+aliasmgr remove alias greet
+__aliasmgr_prompt_sync
+! declare -F greet >/dev/null || exit 65
+"#;
+    assert_success(run_shell("bash", script, catalog.path()).unwrap());
+}
+
+#[test]
+fn variables_are_exported_and_restored_when_no_longer_managed() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2")"
+export EDITOR=vi
+unset PAGER
+aliasmgr env set EDITOR 'nvim -p'
+aliasmgr env set PAGER less
+__aliasmgr_prompt_sync
+[ "$EDITOR" = 'nvim -p' ] || exit 70
+[ "$(sh -c 'echo "$PAGER"')" = less ] || exit 71
+aliasmgr env set EDITOR hx
+__aliasmgr_prompt_sync
+[ "$EDITOR" = hx ] || exit 72
+aliasmgr env disable EDITOR
+aliasmgr env remove PAGER
+__aliasmgr_prompt_sync
+[ "$EDITOR" = vi ] || exit 73
+[ -z "${PAGER+set}" ] || exit 74
+"#;
+    assert_success(run_shell("bash", script, catalog.path()).unwrap());
+}
+
+#[test]
+fn nested_shells_restore_the_value_from_before_aliasmgr() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2")"
+export EDITOR=vi
+aliasmgr env set EDITOR nvim
+__aliasmgr_prompt_sync
+bash --noprofile --norc -c '
+eval "$("$1" init bash --catalog "$2")"
+__aliasmgr_prompt_sync
+[ "$EDITOR" = nvim ] || exit 75
+aliasmgr env remove EDITOR
+__aliasmgr_prompt_sync
+[ "$EDITOR" = vi ] || exit 76
+' nested "$1" "$2" || exit $?
+__aliasmgr_prompt_sync
+[ "$EDITOR" = vi ] || exit 77
+"#;
+    assert_success(run_shell("bash", script, catalog.path()).unwrap());
+}
+
 #[test]
 fn zsh_prompt_sync_reconciles_regular_and_global_aliases() {
     let catalog = tempfile::NamedTempFile::new().unwrap();
@@ -112,9 +183,14 @@ fn zsh_prompt_sync_reconciles_regular_and_global_aliases() {
 eval "$("$1" init zsh --catalog "$2")"
 aliasmgr add smoke 'echo smoke'
 aliasmgr add glob '*.rs' --global
+aliasmgr add txt less --suffix
 __aliasmgr_prompt_sync
 alias smoke | command grep -q 'echo smoke' || exit 50
 alias -g glob | command grep -Fq '*.rs' || exit 51
+alias -s txt | command grep -q less || exit 52
+aliasmgr remove alias txt
+__aliasmgr_prompt_sync
+! alias -s txt >/dev/null || exit 53
 "#;
     match run_shell("zsh", script, catalog.path()) {
         Ok(output) => assert_success(output),
@@ -122,3 +198,141 @@ alias -g glob | command grep -Fq '*.rs' || exit 51
         Err(error) => panic!("failed to run zsh: {error}"),
     }
 }
+
+#[test]
+fn fish_prompt_sync_reconciles_catalog() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+$argv[1] init fish --catalog $argv[2] | source
+aliasmgr add smoke 'echo smoke'
+aliasmgr add quoted "echo 'it\\'s quoted'"
+__aliasmgr_prompt_sync
+functions -q smoke; or exit 60
+test (smoke) = smoke; or exit 61
+test (quoted) = "it's quoted"; or exit 62
+aliasmgr remove alias smoke
+__aliasmgr_prompt_sync
+not functions -q smoke; or exit 63
+"#;
+    match run_shell("fish", script, catalog.path()) {
+        Ok(output) => assert_success(output),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => panic!("failed to run fish: {error}"),
+    }
+}
+
+#[test]
+fn posix_shell_syncs_after_aliasmgr_commands() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init posix --catalog "$2")"
+aliasmgr add smoke 'echo smoke'
+alias smoke | command grep -q 'echo smoke' || exit 60
+aliasmgr remove alias missing 2>/dev/null
+[ "$?" -ne 0 ] || exit 61
+printf '%s\n' 'other = "echo other"' >> "$2"
+aliasmgr list >/dev/null
+alias other >/dev/null || exit 62
+aliasmgr remove alias smoke
+! alias smoke 2>/dev/null || exit 63
+"#;
+    match run_shell("dash", script, catalog.path()) {
+        Ok(output) => assert_success(output),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => panic!("failed to run dash: {error}"),
+    }
+}
+
+#[test]
+fn status_compares_terminal_and_catalog_revisions() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2" --no-auto-sync)"
+[ "$(aliasmgr status --terminal-revision)" = "$(aliasmgr status --revision)" ] || exit 70
+aliasmgr add smoke 'echo smoke'
+aliasmgr status | command grep -q 'out of sync' || exit 71
+aliasmgr sync
+aliasmgr status | command grep -q 'in sync with the catalog' || exit 72
+case "$(aliasmgr status --revision)" in
+    v1-????????????????) ;;
+    *) exit 73 ;;
+esac
+"#;
+    assert_success(run_shell("bash", script, catalog.path()).unwrap());
+}
+
+#[test]
+fn prompt_sync_skips_parsing_when_catalog_metadata_is_unchanged() {
+    let catalog = tempfile::NamedTempFile::new().unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2")"
+aliasmgr add smoke 'echo smoke'
+__aliasmgr_prompt_sync
+[ -n "$__aliasmgr_catalog_stamp" ] || exit 80
+reference="$(mktemp)"
+touch -r "$2" "$reference"
+size="$(wc -c < "$2")"
+head -c "$size" /dev/zero | tr '\0' '[' > "$2"
+touch -r "$reference" "$2"
+rm -f "$reference"
+[ -z "$(__aliasmgr_prompt_sync 2>&1)" ] || exit 81
+! aliasmgr status >/dev/null 2>&1 || exit 82
+"#;
+    assert_success(run_shell("bash", script, catalog.path()).unwrap());
+}
+
+#[test]
+fn project_aliases_load_inside_trusted_projects_and_unload_on_leaving() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    let project = directory.path().join("repo");
+    std::fs::write(&catalog, "").unwrap();
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(project.join(".aliasmgr.toml"), "b = \"make build\"\n").unwrap();
+    let script = r#"
+eval "$("$1" init bash --catalog "$2")"
+__aliasmgr_prompt_sync
+cd "$(dirname "$2")/repo/src" || exit 90
+__aliasmgr_prompt_sync 2>/dev/null
+! alias b 2>/dev/null || exit 91
+aliasmgr trust >/dev/null || exit 92
+__aliasmgr_prompt_sync
+alias b | command grep -q 'make build' || exit 93
+cd / || exit 94
+__aliasmgr_prompt_sync
+! alias b 2>/dev/null || exit 95
+"#;
+    assert_success(run_shell("bash", script, &catalog).unwrap());
+}
+
+#[test]
+fn profiles_switch_aliases_persistently_and_per_terminal() {
+    let directory = tempfile::tempdir().unwrap();
+    let catalog = directory.path().join("aliases.toml");
+    std::fs::write(
+        &catalog,
+        "deploy = { command = \"make deploy\", tags = [\"work\"] }\nll = \"ls -la\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        directory.path().join("config.toml"),
+        "[profiles]\nwork = \"work\"\nhome = \"!work\"\n",
+    )
+    .unwrap();
+    let script = r#"
+export ALIASMGR_CONFIG_PATH="$(dirname "$2")/config.toml"
+eval "$("$1" init bash --catalog "$2")"
+__aliasmgr_prompt_sync
+alias ll >/dev/null || exit 100
+aliasmgr profile use work >/dev/null || exit 101
+__aliasmgr_prompt_sync
+! alias ll 2>/dev/null || exit 102
+alias deploy >/dev/null || exit 103
+export ALIASMGR_PROFILE=home
+__aliasmgr_prompt_sync
+alias ll >/dev/null || exit 104
+! alias deploy 2>/dev/null || exit 105
+aliasmgr list | command grep -q '^Profile: home (!work)$' || exit 106
+"#;
+    assert_success(run_shell("bash", script, &catalog).unwrap());
+}